@@ -4,7 +4,6 @@ mod conjecture;
 #[cfg(test)]
 mod tests {
     use super::conjecture::goldbach_conjecture;
-    use std::time::{Instant, Duration};
 
     // 定义测试用例和预期结果
     const TEST_CASE: &str = "5777,5993";
@@ -12,13 +11,11 @@ mod tests {
     // 定义一个测试函数来验证每个测试用例
     #[test]
     fn test_conjecture() {
-        let start = Instant::now();
+        // 耗时由评测器按 exercise_config.json 中的 budgets_ms 检查
         let result = goldbach_conjecture();
-        let duration = start.elapsed();
 
-        // 时间超0.5s，判定不合格
         let mut total_score = 0.0;
-        if duration <= Duration::from_millis(200) && result == TEST_CASE {
+        if result == TEST_CASE {
             total_score += 100.0;
         }
 
@@ -0,0 +1,64 @@
+// src/tests.rs
+mod prime_factor;
+
+#[cfg(test)]
+mod tests {
+    use super::prime_factor::find_max_prime_factor;
+
+    // 每个测试用例单独作为一个测试，耗时由评测器按 exercise_config.json 中的 budgets_ms 逐个检查
+    #[test]
+    fn test_max_prime_factor_01() {
+        assert_eq!(find_max_prime_factor(10000071), 370373);
+    }
+
+    #[test]
+    fn test_max_prime_factor_02() {
+        assert_eq!(find_max_prime_factor(600851475143), 6857);
+    }
+
+    #[test]
+    fn test_max_prime_factor_03() {
+        assert_eq!(find_max_prime_factor(1600851475143), 16807369);
+    }
+
+    #[test]
+    fn test_max_prime_factor_04() {
+        assert_eq!(find_max_prime_factor(76008514751430), 2163013);
+    }
+
+    #[test]
+    fn test_max_prime_factor_05() {
+        assert_eq!(find_max_prime_factor(96008514751430), 223275615701);
+    }
+
+    #[test]
+    fn test_max_prime_factor_06() {
+        assert_eq!(find_max_prime_factor(99999999951437), 5218879);
+    }
+
+    #[test]
+    fn test_max_prime_factor_07() {
+        assert_eq!(find_max_prime_factor(1199999999951437), 3945019577);
+    }
+
+    #[test]
+    fn test_max_prime_factor_08() {
+        assert_eq!(find_max_prime_factor(9999999999999951437), 387792298444951);
+    }
+
+    #[test]
+    fn test_max_prime_factor_09() {
+        assert_eq!(
+            find_max_prime_factor(97993999919999958437),
+            203729729563409477
+        );
+    }
+
+    #[test]
+    fn test_max_prime_factor_10() {
+        assert_eq!(
+            find_max_prime_factor(199999999999999951437),
+            9523809523809521497
+        );
+    }
+}
@@ -0,0 +1,26 @@
+// src/tests.rs
+mod district;
+
+#[cfg(test)]
+mod tests {
+    use super::district::count_provinces;
+
+    // 定义测试用例和预期结果
+    const TEST_CASE: &str = "3,3,2,2,1";
+
+    // 定义一个测试函数来验证每个测试用例
+    #[test]
+    fn test_count_provinces() {
+        // 耗时由评测器按 exercise_config.json 中的 budgets_ms 检查
+        let result = count_provinces();
+
+        let mut total_score = 0.0;
+
+        if result == TEST_CASE {
+            total_score += 100.0;
+        }
+
+        println!("Total score: {:.2}", total_score);
+        assert_eq!(100.00, total_score);
+    }
+}
@@ -0,0 +1,70 @@
+// src/tests.rs
+mod retirement;
+
+#[cfg(test)]
+mod tests {
+    use super::retirement::retire_time;
+
+    // 每个测试用例单独作为一个测试，耗时由评测器按 exercise_config.json 中的 budgets_ms 逐个检查
+    #[test]
+    fn test_retirement_time_01() {
+        assert_eq!(
+            retire_time("1971-04", "原法定退休年龄55周岁女职工"),
+            "2026-08,55.33,4"
+        );
+    }
+
+    #[test]
+    fn test_retirement_time_02() {
+        assert_eq!(
+            retire_time("1995-12", "原法定退休年龄50周岁女职工"),
+            "2050-12,55,60"
+        );
+    }
+
+    #[test]
+    fn test_retirement_time_03() {
+        assert_eq!(retire_time("1995-12", "男职工"), "2058-12,63,36");
+    }
+
+    #[test]
+    fn test_retirement_time_04() {
+        assert_eq!(
+            retire_time("2000-12", "原法定退休年龄55周岁女职工"),
+            "2058-12,58,36"
+        );
+    }
+
+    #[test]
+    fn test_retirement_time_05() {
+        assert_eq!(retire_time("2000-12", "男职工"), "2063-12,63,36");
+    }
+
+    #[test]
+    fn test_retirement_time_06() {
+        assert_eq!(retire_time("1965-12", "男职工"), "2026-03,60.25,3");
+    }
+
+    #[test]
+    fn test_retirement_time_07() {
+        assert_eq!(retire_time("1963-12", "男职工"), "2023-12,60,0");
+    }
+
+    #[test]
+    fn test_retirement_time_08() {
+        assert_eq!(
+            retire_time("1963-04", "原法定退休年龄55周岁女职工"),
+            "2018-04,55,0"
+        );
+    }
+
+    #[test]
+    fn test_retirement_time_09() {
+        assert_eq!(retire_time("1964-02", "男职工"), "2024-02,60,0");
+    }
+
+    #[test]
+    fn test_retirement_time_10() {
+        assert_eq!(retire_time("1965-01", "男职工"), "2025-02,60.08,1");
+    }
+}
@@ -0,0 +1,210 @@
+use crate::sandbox::ResourceLimits;
+use crate::{Evaluation, Exercise, ExerciseStatus, EXERCISES_DIR};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
+
+// 评测结果缓存文件
+pub const CACHE_FILE: &str = ".cargotest/cache.json";
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    // 习题源文件、Cargo.toml、Cargo.lock、工具链与评测器版本、资源限制与时间预算的哈希
+    hash: String,
+    evaluation: Evaluation,
+}
+
+// 以内容哈希为键的评测结果缓存，习题和工具链均未变化时直接复用上次的结果
+pub struct BuildCache {
+    // 为 false 时（--no-cache）不读取旧结果，但仍会记录本次结果
+    reuse: bool,
+    toolchain: String,
+    grader: String,
+    entries: Mutex<HashMap<String, CacheEntry>>,
+}
+
+impl BuildCache {
+    // 读取缓存文件，文件不存在或无法解析时从空缓存开始
+    pub fn load(reuse: bool) -> Self {
+        let entries = File::open(CACHE_FILE)
+            .ok()
+            .and_then(|file| serde_json::from_reader(file).ok())
+            .unwrap_or_default();
+        BuildCache {
+            reuse,
+            toolchain: toolchain_version(),
+            grader: grader_version(),
+            entries: Mutex::new(entries),
+        }
+    }
+
+    // 不读写缓存文件，用于 verify 等只评测单题的场景
+    pub fn disabled() -> Self {
+        BuildCache {
+            reuse: false,
+            toolchain: String::new(),
+            grader: String::new(),
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // 计算习题当前内容及评测设置的哈希，无法读取文件或缓存已禁用时返回 None
+    pub fn hash(&self, exercise: &Exercise, limits: &ResourceLimits) -> Option<String> {
+        if self.toolchain.is_empty() {
+            return None;
+        }
+        let mut hasher = Fnv1a::default();
+        hasher.write(self.toolchain.as_bytes());
+        hasher.write(self.grader.as_bytes());
+        hasher.write(exercise.exercise_type.as_str().as_bytes());
+        hasher.write(&exercise.timeout().as_secs().to_le_bytes());
+        // 资源限制收紧后，上次通过的测试可能不再通过
+        for limit in [
+            limits.memory_mb,
+            limits.cpu_secs,
+            // 未设置进程数上限时记为 0，配置中的上限总是正数
+            limits.processes.unwrap_or(0),
+            limits.file_size_mb,
+        ] {
+            hasher.write(&limit.to_le_bytes());
+        }
+        // 时间预算或计时次数变化时，计时结论需要重新测量
+        hasher.write(&exercise.repetitions().to_le_bytes());
+        for (test, budget_ms) in &exercise.budgets_ms {
+            hasher.write(test.as_bytes());
+            hasher.write(&budget_ms.to_le_bytes());
+        }
+
+        hash_files(&mut hasher, &Path::new(EXERCISES_DIR).join(&exercise.path))?;
+        // 隐藏测试变化时同样需要重新评测
+        if let Some(hidden_tests) = exercise.hidden_tests_path() {
+            hasher.write(b"hidden_tests");
+            hash_files(&mut hasher, &hidden_tests)?;
+        }
+        Some(format!("{:016x}", hasher.finish()))
+    }
+
+    // 查找与当前内容一致的上次结果，超时的结果不会被复用
+    pub fn lookup(&self, exercise: &Exercise, hash: &str) -> Option<Evaluation> {
+        if !self.reuse {
+            return None;
+        }
+        let entries = self.entries.lock().unwrap();
+        entries
+            .get(&exercise.name)
+            .filter(|entry| entry.hash == hash)
+            .filter(|entry| entry.evaluation.status != ExerciseStatus::TimedOut)
+            .map(|entry| entry.evaluation.clone())
+    }
+
+    pub fn store(&self, exercise: &Exercise, hash: String, evaluation: &Evaluation) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.insert(
+            exercise.name.clone(),
+            CacheEntry {
+                hash,
+                evaluation: evaluation.clone(),
+            },
+        );
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        if self.toolchain.is_empty() {
+            return Ok(());
+        }
+        let path = Path::new(CACHE_FILE);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let entries = self.entries.lock().unwrap();
+        let file = File::create(path)?;
+        serde_json::to_writer(file, &*entries)?;
+        Ok(())
+    }
+}
+
+// 按相对路径排序后依次写入 root 下全部文件的路径和内容
+fn hash_files(hasher: &mut Fnv1a, root: &Path) -> Option<()> {
+    let mut files = Vec::new();
+    collect_files(root, &mut files).ok()?;
+    files.sort();
+    for file in files {
+        let relative = file.strip_prefix(root).unwrap_or(&file);
+        hasher.write(relative.to_string_lossy().as_bytes());
+        hasher.write(&fs::read(&file).ok()?);
+    }
+    Some(())
+}
+
+// rustc 与 cargo 的版本信息，任一变化都会使缓存失效
+fn toolchain_version() -> String {
+    let mut version = String::new();
+    for (program, arg) in [("rustc", "-vV"), ("cargo", "-V")] {
+        if let Ok(output) = Command::new(program).arg(arg).output() {
+            version.push_str(&String::from_utf8_lossy(&output.stdout));
+        }
+    }
+    version
+}
+
+// 评测器的版本号与可执行文件的大小和修改时间，重新编译评测器（例如评分规则变化）后旧结果随之失效
+fn grader_version() -> String {
+    let binary = env::current_exe()
+        .and_then(fs::metadata)
+        .map(|metadata| {
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .unwrap_or_default();
+            format!("{} {}", metadata.len(), modified.as_nanos())
+        })
+        .unwrap_or_default();
+    format!("{} {}", env!("CARGO_PKG_VERSION"), binary)
+}
+
+// 收集习题的全部文件，跳过 target 目录和隐藏文件
+pub fn collect_files(path: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    if path.is_file() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+    for entry in fs::read_dir(path)? {
+        let path = entry?.path();
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        if name == "target" || name.starts_with('.') {
+            continue;
+        }
+        collect_files(&path, files)?;
+    }
+    Ok(())
+}
+
+// 64 位 FNV-1a，结果不随 Rust 版本变化，适合写入缓存文件
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv1a {
+    // 先写入长度，避免相邻字段拼接后产生歧义
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in (bytes.len() as u64).to_le_bytes().iter().chain(bytes) {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
@@ -0,0 +1,491 @@
+use crate::logging::Verbosity;
+use crate::selection::Selection;
+use crate::style::ColorChoice;
+use crate::{Difficulty, ExerciseType};
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+Usage: cargotest <command> [options]
+
+Commands:
+  run            Evaluate exercises and save the report (alias: all)
+  watch          Re-evaluate exercises whenever their files change
+  tui            Evaluate exercises in a full-screen terminal dashboard
+  list           List exercises with their type, score and last known status
+  verify <name>  Evaluate a single exercise without touching the saved report
+  hint <name>    Show the hint for an exercise
+  report [path]  Pretty-print a saved report (default: report.json)
+  seal           Record the hashes of protected files in exercise_config.json
+  new            Create a new exercise and register it in exercise_config.json
+  reset <name>   Restore an exercise to its starter version
+  help [command] Show help for a command
+
+Global options:
+  -v, --verbose      Also print the commands being run and cleanup details
+  -q, --quiet        Only print failures, errors and the summary
+      --color <when> Colour the output: auto, always or never (default: auto,
+                     which disables colour when NO_COLOR is set or the output
+                     is not a terminal)
+
+Every evaluation step is also logged to the rolling log file configured in
+log4rs.yaml (by default .cargotest/logs/cargotest.log).
+
+Run 'cargotest <command> --help' for more information on a command.
+
+Exit status:
+  0  Every selected exercise passed and the score thresholds were met
+  1  Grading failed: an exercise failed or the score is below a threshold
+  2  Invalid command-line usage
+  3  exercise_config.json could not be read or is invalid
+  4  Grading could not be completed (e.g. no exercise matches the selection)
+";
+
+const RUN_HELP: &str = "\
+Usage: cargotest run [options]
+
+Evaluate the exercises listed in exercise_config.json and save the results
+to report.json and report.html.
+
+Options:
+  -j, --jobs <n>           Evaluate up to <n> exercises concurrently (default: 1)
+      --junit <path>       Also write a JUnit XML report to <path>
+      --difficulty <level> Only evaluate easy, normal or hard exercises (repeatable)
+      --only <pattern>     Only evaluate exercises matching a name or glob (repeatable)
+      --from <name>        Start from the given exercise, skipping earlier ones
+      --resume             Start from the first exercise not yet solved in an
+                           earlier run (see .cargotest/state.json)
+      --no-cache           Re-evaluate exercises even if their sources are unchanged
+      --shared-target      Build all cargo exercises in one target directory that is
+                           kept between runs (.cargotest/target)
+      --fail-under <score> Exit with status 1 if the total score is below <score>
+      --fail-under-percent <p>
+                           Exit with status 1 if the total score is below <p>% of
+                           the maximum score of the selected exercises
+  -h, --help               Show this help
+
+Exits with status 1 if any selected exercise fails (in-progress exercises are
+not counted as failures) or a --fail-under threshold is not met.
+";
+
+const WATCH_HELP: &str = "\
+Usage: cargotest watch [options]
+
+Evaluate the selected exercises, starting from the first one not yet solved,
+then watch exercises/ and re-evaluate an exercise as soon as one of its files
+changes. Runs until every exercise passes or you type 'q' and press Enter.
+
+Options:
+  -j, --jobs <n>           Evaluate up to <n> exercises concurrently (default: 1)
+      --junit <path>       Also write a JUnit XML report to <path>
+      --difficulty <level> Only watch easy, normal or hard exercises (repeatable)
+      --only <pattern>     Only watch exercises matching a name or glob (repeatable)
+      --from <name>        Start from the given exercise, skipping earlier ones
+      --no-cache           Re-evaluate exercises even if their sources are unchanged
+      --shared-target      Build all cargo exercises in one target directory that is
+                           kept between runs (.cargotest/target)
+      --fail-under <score> Exit with status 1 if the final total score is below <score>
+      --fail-under-percent <p>
+                           Exit with status 1 if the final total score is below <p>%
+                           of the maximum score of the watched exercises
+  -h, --help               Show this help
+";
+
+const TUI_HELP: &str = "\
+Usage: cargotest tui [options]
+
+Evaluate the selected exercises in a full-screen dashboard that shows the live
+status of every exercise (queued, building, testing, clippy, passed or failed)
+and the running score. The report is saved when you quit; exercises that were
+not evaluated yet are left out of it.
+
+Keys:
+  Up/Down, k/j             Select an exercise and show its output
+  PageUp/PageDown          Scroll the output of the selected exercise
+  r                        Re-run the selected exercise
+  q, Esc                   Quit (waits for running exercises to finish)
+
+Options:
+  -j, --jobs <n>           Evaluate up to <n> exercises concurrently (default: 1)
+      --junit <path>       Also write a JUnit XML report to <path>
+      --difficulty <level> Only show easy, normal or hard exercises (repeatable)
+      --only <pattern>     Only show exercises matching a name or glob (repeatable)
+      --from <name>        Start from the given exercise, skipping earlier ones
+      --no-cache           Re-evaluate exercises even if their sources are unchanged
+      --shared-target      Build all cargo exercises in one target directory that is
+                           kept between runs (.cargotest/target)
+  -h, --help               Show this help
+";
+
+const LIST_HELP: &str = "\
+Usage: cargotest list
+
+List every exercise with its difficulty, type, score and last known status,
+followed by a progress bar for each difficulty.
+";
+
+const VERIFY_HELP: &str = "\
+Usage: cargotest verify <name>
+
+Evaluate a single exercise and print the result. The saved report is left
+unchanged. Exits with status 0 if the exercise passes and 1 otherwise.
+";
+
+const HINT_HELP: &str = "\
+Usage: cargotest hint <name>
+
+Show the hint for an exercise: the `hint` entry from exercise_config.json,
+or the description at the top of the exercise's source file.
+";
+
+const REPORT_HELP: &str = "\
+Usage: cargotest report [path]
+
+Pretty-print a saved JSON report (default: report.json).
+";
+
+const SEAL_HELP: &str = "\
+Usage: cargotest seal
+
+Recompute the SHA-256 hashes of the protected files listed under `protected`
+in exercise_config.json and write them back. Exercises whose protected files
+no longer match are reported as tampered and are not evaluated.
+";
+
+const NEW_HELP: &str = "\
+Usage: cargotest new --difficulty <level> --name <name> [options]
+
+Create the skeleton of a new exercise under exercises/<level>/ and register it
+in exercise_config.json. The generated tests print the standard \"Total score\"
+line; fill in the test cases, then run 'cargotest seal' to record the hash of
+the finished tests. The exercise starts with a `// I AM NOT DONE` marker.
+
+Options:
+      --difficulty <level> easy, normal or hard
+      --name <name>        Name of the exercise (.rs is added for single files)
+      --kind <kind>        cargo_project or single_file (default: single_file
+                           for easy exercises, cargo_project otherwise)
+      --score <n>          Score of the exercise (default: the score of the last
+                           exercise with the same difficulty)
+  -h, --help               Show this help
+";
+
+const RESET_HELP: &str = "\
+Usage: cargotest reset <name> [options]
+
+Restore the files of an exercise that students edit to their starter version.
+Test files (protected files, src/tests.rs, files replaced by hidden tests and
+the #[cfg(test)] module of a single-file exercise) are left unchanged. The
+current files are first copied to .cargotest/backups/<name>-<timestamp>.
+
+The starter version is read from the exercise's `starter` copy under
+starters/ or, if it has none, from the git revision `starter_revision` in
+exercise_config.json. Neither is configured by default; the starter must
+contain the unsolved exercise, not a solved one.
+
+Options:
+  -y, --yes                Do not ask for confirmation
+  -h, --help               Show this help
+";
+
+// 退出码：命令行用法错误
+pub const EXIT_USAGE: i32 = 2;
+
+// 子命令及其参数
+#[derive(Debug)]
+pub enum Subcommand {
+    Run(Options),
+    Watch(Options),
+    Tui(Options),
+    List,
+    Verify(String),
+    Hint(String),
+    Report(PathBuf),
+    Seal,
+    New(NewOptions),
+    // 习题名，以及是否跳过确认
+    Reset(String, bool),
+    Help(Option<String>),
+}
+
+// new 的命令行选项
+#[derive(Debug)]
+pub struct NewOptions {
+    pub difficulty: Difficulty,
+    pub name: String,
+    pub exercise_type: ExerciseType,
+    // 未指定时使用同一难度中最后一道习题的分值
+    pub score: Option<i32>,
+}
+
+// run 与 watch 共用的命令行选项
+#[derive(Debug)]
+pub struct Options {
+    // 并发评测的习题数，默认串行评测
+    pub jobs: usize,
+    // 额外输出 JUnit XML 报告的路径
+    pub junit: Option<PathBuf>,
+    // 需要评测的习题范围
+    pub selection: Selection,
+    // 忽略缓存，重新评测全部习题
+    pub no_cache: bool,
+    // 所有 Cargo 习题共用一个构建目录，并在评测之间保留
+    pub shared_target: bool,
+    // 从第一道尚未通过的习题开始评测（watch 模式总是如此）
+    pub resume: bool,
+    // 总分低于该值时以 EXIT_FAILURE 退出
+    pub fail_under: Option<f64>,
+    // 总分低于所选习题满分的该百分比时以 EXIT_FAILURE 退出
+    pub fail_under_percent: Option<f64>,
+}
+
+// 返回子命令的帮助信息，未知子命令返回 None
+pub fn help_text(command: Option<&str>) -> Option<&'static str> {
+    match command {
+        None => Some(USAGE),
+        Some("run" | "all") => Some(RUN_HELP),
+        Some("watch") => Some(WATCH_HELP),
+        Some("tui") => Some(TUI_HELP),
+        Some("list") => Some(LIST_HELP),
+        Some("verify") => Some(VERIFY_HELP),
+        Some("hint") => Some(HINT_HELP),
+        Some("report") => Some(REPORT_HELP),
+        Some("seal") => Some(SEAL_HELP),
+        Some("new") => Some(NEW_HELP),
+        Some("reset") => Some(RESET_HELP),
+        Some(_) => None,
+    }
+}
+
+// 对所有命令都有效的选项
+#[derive(Debug, Default)]
+pub struct GlobalOptions {
+    pub verbosity: Verbosity,
+    pub color: ColorChoice,
+}
+
+// 解析命令行参数，-v / -q / --color 可出现在任意位置
+pub fn parse_args(args: &[String]) -> Result<(Subcommand, GlobalOptions), String> {
+    let mut global = GlobalOptions::default();
+    let mut rest = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-v" | "--verbose" => global.verbosity = Verbosity::Verbose,
+            "-q" | "--quiet" => global.verbosity = Verbosity::Quiet,
+            "--color" => {
+                let value = iter.next().ok_or("--color requires a value")?;
+                global.color = parse_color(value)?;
+            }
+            arg => match arg.strip_prefix("--color=") {
+                Some(value) => global.color = parse_color(value)?,
+                None => rest.push(arg.to_string()),
+            },
+        }
+    }
+    Ok((parse_command(&rest)?, global))
+}
+
+fn parse_color(value: &str) -> Result<ColorChoice, String> {
+    ColorChoice::parse(value).ok_or_else(|| {
+        format!(
+            "Invalid value for --color: {} (expected auto, always or never)",
+            value
+        )
+    })
+}
+
+fn parse_command(args: &[String]) -> Result<Subcommand, String> {
+    let Some((command, rest)) = args.split_first() else {
+        return Ok(Subcommand::Help(None));
+    };
+
+    let wants_help = rest.iter().any(|arg| arg == "-h" || arg == "--help");
+    match command.as_str() {
+        "-h" | "--help" => return Ok(Subcommand::Help(None)),
+        "help" => return Ok(Subcommand::Help(rest.first().cloned())),
+        command if wants_help && help_text(Some(command)).is_some() => {
+            return Ok(Subcommand::Help(Some(command.to_string())))
+        }
+        _ => {}
+    }
+
+    match command.as_str() {
+        // all 为旧版命令，保留以兼容已有的 CI 配置
+        "run" | "all" => Ok(Subcommand::Run(parse_options(rest)?)),
+        "watch" => Ok(Subcommand::Watch(parse_options(rest)?)),
+        "tui" => Ok(Subcommand::Tui(parse_options(rest)?)),
+        "list" => {
+            expect_no_more(command, rest)?;
+            Ok(Subcommand::List)
+        }
+        "seal" => {
+            expect_no_more(command, rest)?;
+            Ok(Subcommand::Seal)
+        }
+        "new" => Ok(Subcommand::New(parse_new_options(rest)?)),
+        "reset" => {
+            let yes = rest.iter().any(|arg| arg == "-y" || arg == "--yes");
+            let rest: Vec<String> = rest
+                .iter()
+                .filter(|arg| *arg != "-y" && *arg != "--yes")
+                .cloned()
+                .collect();
+            Ok(Subcommand::Reset(single_name(command, &rest)?, yes))
+        }
+        "verify" => Ok(Subcommand::Verify(single_name(command, rest)?)),
+        "hint" => Ok(Subcommand::Hint(single_name(command, rest)?)),
+        "report" => match rest {
+            [] => Ok(Subcommand::Report(PathBuf::from(crate::REPORT_FILE))),
+            [path] => Ok(Subcommand::Report(PathBuf::from(path))),
+            _ => Err(format!("Unexpected argument for report: {}", rest[1])),
+        },
+        _ => Err(format!("Unknown command: {}", command)),
+    }
+}
+
+fn expect_no_more(command: &str, rest: &[String]) -> Result<(), String> {
+    match rest.first() {
+        Some(arg) => Err(format!("Unexpected argument for {}: {}", command, arg)),
+        None => Ok(()),
+    }
+}
+
+fn single_name(command: &str, rest: &[String]) -> Result<String, String> {
+    match rest {
+        [name] if !name.starts_with('-') => Ok(name.clone()),
+        [] => Err(format!("Missing exercise name for {}", command)),
+        [arg] => Err(format!("Unknown argument: {}", arg)),
+        _ => Err(format!("Unexpected argument for {}: {}", command, rest[1])),
+    }
+}
+
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        jobs: 1,
+        junit: None,
+        selection: Selection::default(),
+        no_cache: false,
+        shared_target: false,
+        resume: false,
+        fail_under: None,
+        fail_under_percent: None,
+    };
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let (name, inline_value) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name, Some(value)),
+            _ => (arg.as_str(), None),
+        };
+        let mut value = || {
+            inline_value
+                .or_else(|| iter.next().map(String::as_str))
+                .ok_or_else(|| format!("Missing value for {}", name))
+        };
+        match name {
+            "--jobs" | "-j" => {
+                let value = value()?;
+                options.jobs = match value.parse::<usize>() {
+                    Ok(n) if n > 0 => n,
+                    _ => return Err(format!("Invalid value for --jobs: {}", value)),
+                };
+            }
+            "--junit" => options.junit = Some(PathBuf::from(value()?)),
+            "--difficulty" => {
+                let value = value()?;
+                let difficulty = Difficulty::parse(value).ok_or_else(|| {
+                    format!(
+                        "Invalid value for --difficulty: {} (expected easy, normal or hard)",
+                        value
+                    )
+                })?;
+                options.selection.difficulties.push(difficulty);
+            }
+            "--only" => options.selection.only.push(value()?.to_string()),
+            "--from" => options.selection.from = Some(value()?.to_string()),
+            "--no-cache" => options.no_cache = true,
+            "--shared-target" => options.shared_target = true,
+            "--resume" => options.resume = true,
+            "--fail-under" => {
+                let value = value()?;
+                options.fail_under = match value.parse::<f64>() {
+                    Ok(score) if score >= 0.0 => Some(score),
+                    _ => return Err(format!("Invalid value for --fail-under: {}", value)),
+                };
+            }
+            "--fail-under-percent" => {
+                let value = value()?;
+                options.fail_under_percent = match value.trim_end_matches('%').parse::<f64>() {
+                    Ok(percent) if (0.0..=100.0).contains(&percent) => Some(percent),
+                    _ => {
+                        return Err(format!(
+                            "Invalid value for --fail-under-percent: {} (expected 0 to 100)",
+                            value
+                        ))
+                    }
+                };
+            }
+            _ => return Err(format!("Unknown argument: {}", arg)),
+        }
+    }
+    Ok(options)
+}
+
+fn parse_new_options(args: &[String]) -> Result<NewOptions, String> {
+    let mut difficulty = None;
+    let mut name = None;
+    let mut exercise_type = None;
+    let mut score = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let (option, inline_value) = match arg.split_once('=') {
+            Some((option, value)) if option.starts_with("--") => (option, Some(value)),
+            _ => (arg.as_str(), None),
+        };
+        let mut value = || {
+            inline_value
+                .or_else(|| iter.next().map(String::as_str))
+                .ok_or_else(|| format!("Missing value for {}", option))
+        };
+        match option {
+            "--difficulty" => {
+                let value = value()?;
+                difficulty = Some(Difficulty::parse(value).ok_or_else(|| {
+                    format!(
+                        "Invalid value for --difficulty: {} (expected easy, normal or hard)",
+                        value
+                    )
+                })?);
+            }
+            "--name" => name = Some(value()?.to_string()),
+            "--kind" => {
+                let value = value()?;
+                exercise_type = Some(ExerciseType::parse(value).ok_or_else(|| {
+                    format!(
+                        "Invalid value for --kind: {} (expected cargo_project or single_file)",
+                        value
+                    )
+                })?);
+            }
+            "--score" => {
+                let value = value()?;
+                score = match value.parse::<i32>() {
+                    Ok(score) if score > 0 => Some(score),
+                    _ => return Err(format!("Invalid value for --score: {}", value)),
+                };
+            }
+            _ => return Err(format!("Unknown argument: {}", arg)),
+        }
+    }
+
+    let difficulty = difficulty.ok_or("Missing --difficulty for new")?;
+    let exercise_type = exercise_type.unwrap_or(match difficulty {
+        Difficulty::Easy => ExerciseType::SingleFile,
+        Difficulty::Normal | Difficulty::Hard => ExerciseType::CargoProject,
+    });
+    Ok(NewOptions {
+        difficulty,
+        name: name.ok_or("Missing --name for new")?,
+        exercise_type,
+        score,
+    })
+}
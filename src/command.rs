@@ -0,0 +1,166 @@
+use std::io::{self, Read};
+use std::os::unix::process::CommandExt;
+use std::process::{Child, Command, ExitStatus, Output, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// 轮询子进程状态的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+// 带时限运行子进程的结果
+#[derive(Debug)]
+pub enum CommandOutcome {
+    Finished(Output),
+    TimedOut,
+}
+
+// 运行命令并在超过 deadline 时结束整个子进程树
+//
+// 子进程被放入独立的进程组，超时后向整个进程组发送 SIGKILL，
+// 这样 cargo 派生出的 rustc 和测试进程也会一并被结束。子进程按时退出后同样
+// 结束进程组，避免仍持有输出管道的后台进程使读取线程一直阻塞。
+pub fn run_with_deadline(command: &mut Command, deadline: Instant) -> io::Result<CommandOutcome> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .process_group(0)
+        .spawn()?;
+
+    let stdout = read_pipe(child.stdout.take());
+    let stderr = read_pipe(child.stderr.take());
+
+    let exited = wait_until(&child, deadline)?;
+    let status = kill_process_group(&mut child)?;
+    if !exited {
+        return Ok(CommandOutcome::TimedOut);
+    }
+
+    Ok(CommandOutcome::Finished(Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    }))
+}
+
+// 运行命令并精确测量其耗时，超过 deadline 时结束整个子进程树并返回 None
+//
+// 与 run_with_deadline 的轮询不同，这里阻塞等待子进程退出，由后台线程负责超时，
+// 因此测得的耗时不受轮询间隔影响。子进程的输出被丢弃。
+pub fn run_timed(
+    command: &mut Command,
+    deadline: Instant,
+) -> io::Result<Option<(ExitStatus, Duration)>> {
+    let start = Instant::now();
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .process_group(0)
+        .spawn()?;
+    let pgid = child.id() as libc::pid_t;
+
+    let (done, finished) = mpsc::channel::<()>();
+    let watchdog = thread::spawn(move || {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        let timed_out = finished.recv_timeout(timeout) == Err(mpsc::RecvTimeoutError::Timeout);
+        if timed_out {
+            unsafe {
+                libc::kill(-pgid, libc::SIGKILL);
+            }
+        }
+        timed_out
+    });
+
+    let status = child.wait();
+    let elapsed = start.elapsed();
+    let _ = done.send(());
+    let timed_out = watchdog.join().unwrap_or(false);
+    let status = status?;
+    Ok((!timed_out).then_some((status, elapsed)))
+}
+
+// 在后台线程中读取管道，避免子进程因管道写满而阻塞
+fn read_pipe<R: Read + Send + 'static>(pipe: Option<R>) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buffer);
+        }
+        buffer
+    })
+}
+
+// 等待子进程退出，超过 deadline 时返回 false
+fn wait_until(child: &Child, deadline: Instant) -> io::Result<bool> {
+    loop {
+        if has_exited(child)? {
+            return Ok(true);
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return Ok(false);
+        }
+        thread::sleep(POLL_INTERVAL.min(deadline - now));
+    }
+}
+
+// 检查子进程是否已退出但不回收它，保证结束进程组前其 pid 不会被复用
+fn has_exited(child: &Child) -> io::Result<bool> {
+    let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+    let ret = unsafe {
+        libc::waitid(
+            libc::P_PID,
+            child.id() as libc::id_t,
+            &mut info,
+            libc::WEXITED | libc::WNOHANG | libc::WNOWAIT,
+        )
+    };
+    if ret != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(unsafe { info.si_pid() } != 0)
+}
+
+// 结束子进程所在的整个进程组并回收子进程
+fn kill_process_group(child: &mut Child) -> io::Result<ExitStatus> {
+    // process_group(0) 使子进程的进程组号等于其 pid
+    let pgid = child.id() as libc::pid_t;
+    unsafe {
+        libc::kill(-pgid, libc::SIGKILL);
+    }
+    child.wait()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 后台进程继承了输出管道，子进程退出后不应等到它结束
+    #[test]
+    fn run_with_deadline_kills_background_processes() {
+        let start = Instant::now();
+        let mut command = Command::new("sh");
+        command.args(["-c", "sleep 30 & echo done"]);
+        let outcome = run_with_deadline(&mut command, start + Duration::from_secs(20)).unwrap();
+        assert!(start.elapsed() < Duration::from_secs(10));
+        match outcome {
+            CommandOutcome::Finished(output) => {
+                assert!(output.status.success());
+                assert_eq!(output.stdout, b"done\n");
+            }
+            CommandOutcome::TimedOut => panic!("command timed out"),
+        }
+    }
+
+    #[test]
+    fn run_with_deadline_times_out() {
+        let start = Instant::now();
+        let mut command = Command::new("sleep");
+        command.arg("30");
+        let outcome = run_with_deadline(&mut command, start + Duration::from_millis(200)).unwrap();
+        assert!(matches!(outcome, CommandOutcome::TimedOut));
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+}
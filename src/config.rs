@@ -0,0 +1,766 @@
+use crate::sandbox::ResourceLimits;
+use crate::timing::DEFAULT_REPETITIONS;
+use crate::{logging, tamper, EXERCISES_DIR, HIDDEN_TESTS_DIR, STARTERS_DIR};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+// 未在配置中指定时，每道习题的默认时限（秒）
+pub const DEFAULT_TIMEOUT_SECS: u64 = 300;
+
+// 习题难度，对应配置文件中的 easy、normal、hard 三组
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Difficulty {
+    #[default]
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Difficulty::ALL
+            .into_iter()
+            .find(|difficulty| difficulty.as_str() == name)
+    }
+}
+
+// 习题类型：单个源文件，或带 Cargo.toml 的 Cargo 项目
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExerciseType {
+    SingleFile,
+    CargoProject,
+}
+
+impl ExerciseType {
+    const ALL: [ExerciseType; 2] = [ExerciseType::SingleFile, ExerciseType::CargoProject];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExerciseType::SingleFile => "single_file",
+            ExerciseType::CargoProject => "cargo_project",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        ExerciseType::ALL
+            .into_iter()
+            .find(|exercise_type| exercise_type.as_str() == name)
+    }
+}
+
+impl fmt::Display for ExerciseType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Exercise {
+    pub name: String,
+    pub path: String,
+    #[serde(rename = "type")]
+    pub exercise_type: ExerciseType,
+    pub score: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    // hint 子命令输出的提示
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+    // 测试名到时间预算（毫秒）的映射，由 grader 计时，不依赖测试自身的检查
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub budgets_ms: BTreeMap<String, u64>,
+    // 有时间预算的测试重复运行的次数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repetitions: Option<u32>,
+    // grader 持有的测试文件或目录（相对 hidden_tests 目录），评测时覆盖学生的测试
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hidden_tests: Option<String>,
+    // 受保护的文件（或单文件习题的测试模块）到其 SHA-256 的映射，由 seal 子命令生成
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub protected: BTreeMap<String, String>,
+    // 习题初始版本的副本（相对 starters 目录），reset 时优先使用
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub starter: Option<String>,
+    // 由习题所在的分组决定，不出现在配置文件中
+    #[serde(skip)]
+    pub difficulty: Difficulty,
+}
+
+impl Exercise {
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS))
+    }
+
+    pub fn repetitions(&self) -> u32 {
+        self.repetitions.unwrap_or(DEFAULT_REPETITIONS)
+    }
+
+    pub fn hidden_tests_path(&self) -> Option<PathBuf> {
+        self.hidden_tests
+            .as_ref()
+            .map(|path| Path::new(HIDDEN_TESTS_DIR).join(path))
+    }
+
+    pub fn starter_path(&self) -> Option<PathBuf> {
+        self.starter
+            .as_ref()
+            .map(|path| Path::new(STARTERS_DIR).join(path))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ExerciseConfig {
+    // 全局默认时限，可被单道习题的 timeout_secs 覆盖
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    // 开启后按测试输出中的 "Total score" 给未完全通过的习题部分分
+    #[serde(default)]
+    pub partial_credit: bool,
+    // 运行测试二进制文件时的资源限制，未设置的项使用默认值
+    #[serde(default)]
+    pub limits: ResourceLimits,
+    // 全局默认的计时重复次数，可被单道习题的 repetitions 覆盖
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repetitions: Option<u32>,
+    // 没有 starter 副本的习题从该 git 版本中恢复初始版本
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub starter_revision: Option<String>,
+    pub easy: Vec<Exercise>,
+    pub normal: Vec<Exercise>,
+    pub hard: Vec<Exercise>,
+}
+
+impl ExerciseConfig {
+    // 按 easy、normal、hard 的顺序展开全部习题，并填入难度、默认时限和计时重复次数
+    pub fn exercises(&self) -> Vec<Exercise> {
+        let default_timeout = self.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS);
+        let mut all_exercises = Vec::new();
+        for (difficulty, exercises) in
+            Difficulty::ALL
+                .into_iter()
+                .zip([&self.easy, &self.normal, &self.hard])
+        {
+            for exercise in exercises {
+                let mut exercise = exercise.clone();
+                exercise.difficulty = difficulty;
+                exercise.timeout_secs.get_or_insert(default_timeout);
+                if let Some(repetitions) = self.repetitions {
+                    exercise.repetitions.get_or_insert(repetitions);
+                }
+                all_exercises.push(exercise);
+            }
+        }
+        all_exercises
+    }
+}
+
+// 配置文件中的一处问题，location 为其在 JSON 中的位置，例如 easy[3].path
+#[derive(Debug)]
+pub struct ConfigProblem {
+    pub location: String,
+    pub message: String,
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(io::Error),
+    Parse(serde_json::Error),
+    Invalid(Vec<ConfigProblem>),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "{}", e),
+            ConfigError::Parse(e) => write!(f, "invalid JSON: {}", e),
+            ConfigError::Invalid(problems) => {
+                write!(f, "found {} problem(s)", problems.len())?;
+                for problem in problems {
+                    write!(f, "\n  {}: {}", problem.location, problem.message)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+// 读取并校验习题配置，校验不通过时一次性返回全部问题
+pub fn load_exercise_config(file_path: &str) -> Result<ExerciseConfig, ConfigError> {
+    logging::in_phase("config", || {
+        let loaded = read_exercise_config(file_path);
+        match &loaded {
+            Ok(config) => log::info!(
+                "Loaded {} exercises from {}",
+                config.exercises().len(),
+                file_path
+            ),
+            Err(e) => log::error!("Failed to load {}: {}", file_path, e),
+        }
+        loaded
+    })
+}
+
+fn read_exercise_config(file_path: &str) -> Result<ExerciseConfig, ConfigError> {
+    let file = File::open(file_path).map_err(ConfigError::Io)?;
+    let value: Value = serde_json::from_reader(file).map_err(ConfigError::Parse)?;
+
+    let problems = validate(
+        &value,
+        Path::new(EXERCISES_DIR),
+        Path::new(HIDDEN_TESTS_DIR),
+        Path::new(STARTERS_DIR),
+    );
+    if !problems.is_empty() {
+        return Err(ConfigError::Invalid(problems));
+    }
+    serde_json::from_value(value).map_err(ConfigError::Parse)
+}
+
+const CONFIG_KEYS: &[&str] = &[
+    "timeout_secs",
+    "partial_credit",
+    "limits",
+    "repetitions",
+    "starter_revision",
+    "easy",
+    "normal",
+    "hard",
+];
+
+const LIMIT_KEYS: &[&str] = &["memory_mb", "cpu_secs", "processes", "file_size_mb"];
+
+const EXERCISE_KEYS: &[&str] = &[
+    "name",
+    "path",
+    "type",
+    "score",
+    "timeout_secs",
+    "hint",
+    "budgets_ms",
+    "repetitions",
+    "hidden_tests",
+    "protected",
+    "starter",
+];
+
+struct Validator<'a> {
+    exercises_dir: &'a Path,
+    hidden_tests_dir: &'a Path,
+    starters_dir: &'a Path,
+    problems: Vec<ConfigProblem>,
+    // 已出现的习题名及其位置，用于检查重名
+    names: HashMap<String, String>,
+}
+
+impl Validator<'_> {
+    fn problem(&mut self, location: impl Into<String>, message: impl Into<String>) {
+        self.problems.push(ConfigProblem {
+            location: location.into(),
+            message: message.into(),
+        });
+    }
+
+    fn check_unknown_keys(&mut self, location: &str, object: &Map<String, Value>, known: &[&str]) {
+        for key in object.keys() {
+            if !known.contains(&key.as_str()) {
+                let location = if location.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", location, key)
+                };
+                self.problem(location, "unknown field");
+            }
+        }
+    }
+
+    fn check_timeout(&mut self, location: String, value: Option<&Value>) {
+        if let Some(value) = value {
+            if value.as_u64().is_none_or(|secs| secs == 0) {
+                self.problem(location, "expected a positive integer number of seconds");
+            }
+        }
+    }
+
+    fn check_repetitions(&mut self, location: String, value: Option<&Value>) {
+        if let Some(value) = value {
+            if value.as_u64().is_none_or(|n| n == 0 || n > u32::MAX as u64) {
+                self.problem(location, "expected a positive integer");
+            }
+        }
+    }
+
+    fn check_config(&mut self, value: &Value) {
+        let Some(config) = value.as_object() else {
+            self.problem("$", "expected an object");
+            return;
+        };
+        self.check_unknown_keys("", config, CONFIG_KEYS);
+        self.check_timeout("timeout_secs".to_string(), config.get("timeout_secs"));
+        self.check_repetitions("repetitions".to_string(), config.get("repetitions"));
+        if config
+            .get("partial_credit")
+            .is_some_and(|v| !v.is_boolean())
+        {
+            self.problem("partial_credit", "expected true or false");
+        }
+        if config
+            .get("starter_revision")
+            .is_some_and(|v| v.as_str().is_none_or(str::is_empty))
+        {
+            self.problem("starter_revision", "expected a git revision");
+        }
+        match config.get("limits") {
+            None => {}
+            Some(Value::Object(limits)) => {
+                self.check_unknown_keys("limits", limits, LIMIT_KEYS);
+                for (key, value) in limits {
+                    if LIMIT_KEYS.contains(&key.as_str()) && value.as_u64().is_none_or(|n| n == 0) {
+                        self.problem(format!("limits.{}", key), "expected a positive integer");
+                    }
+                }
+            }
+            Some(_) => self.problem("limits", "expected an object"),
+        }
+
+        for difficulty in Difficulty::ALL {
+            let group = difficulty.as_str();
+            match config.get(group) {
+                None => self.problem(group, "missing field"),
+                Some(Value::Array(exercises)) => {
+                    for (index, exercise) in exercises.iter().enumerate() {
+                        self.check_exercise(&format!("{}[{}]", group, index), exercise);
+                    }
+                }
+                Some(_) => self.problem(group, "expected an array of exercises"),
+            }
+        }
+    }
+
+    fn check_exercise(&mut self, location: &str, value: &Value) {
+        let Some(exercise) = value.as_object() else {
+            self.problem(location, "expected an object");
+            return;
+        };
+        self.check_unknown_keys(location, exercise, EXERCISE_KEYS);
+
+        match exercise.get("name") {
+            Some(Value::String(name)) if !name.is_empty() => {
+                let name_location = format!("{}.name", location);
+                if let Some(first) = self.names.get(name) {
+                    let message = format!(
+                        "duplicate exercise name \"{}\" (first defined at {})",
+                        name, first
+                    );
+                    self.problem(name_location, message);
+                } else {
+                    self.names.insert(name.clone(), name_location);
+                }
+            }
+            Some(_) => self.problem(format!("{}.name", location), "expected a non-empty string"),
+            None => self.problem(format!("{}.name", location), "missing field"),
+        }
+
+        let exercise_type = match exercise.get("type") {
+            Some(Value::String(name)) => {
+                let parsed = ExerciseType::parse(name);
+                if parsed.is_none() {
+                    self.problem(
+                        format!("{}.type", location),
+                        format!(
+                            "unknown exercise type \"{}\" (expected single_file or cargo_project)",
+                            name
+                        ),
+                    );
+                }
+                parsed
+            }
+            Some(_) => {
+                self.problem(format!("{}.type", location), "expected a string");
+                None
+            }
+            None => {
+                self.problem(format!("{}.type", location), "missing field");
+                None
+            }
+        };
+
+        match exercise.get("path") {
+            Some(Value::String(path)) => self.check_path(location, path, exercise_type),
+            Some(_) => self.problem(format!("{}.path", location), "expected a string"),
+            None => self.problem(format!("{}.path", location), "missing field"),
+        }
+
+        match exercise.get("score") {
+            Some(score) if score.as_i64().is_some_and(|score| score > 0) => {
+                if score.as_i64().is_some_and(|score| score > i32::MAX as i64) {
+                    self.problem(format!("{}.score", location), "score is too large");
+                }
+            }
+            Some(_) => self.problem(format!("{}.score", location), "expected a positive integer"),
+            None => self.problem(format!("{}.score", location), "missing field"),
+        }
+
+        self.check_timeout(
+            format!("{}.timeout_secs", location),
+            exercise.get("timeout_secs"),
+        );
+        if exercise.get("hint").is_some_and(|hint| !hint.is_string()) {
+            self.problem(format!("{}.hint", location), "expected a string");
+        }
+        self.check_repetitions(
+            format!("{}.repetitions", location),
+            exercise.get("repetitions"),
+        );
+        match exercise.get("budgets_ms") {
+            None => {}
+            Some(Value::Object(budgets)) => {
+                for (test, budget) in budgets {
+                    if budget.as_u64().is_none_or(|ms| ms == 0) {
+                        self.problem(
+                            format!("{}.budgets_ms.{}", location, test),
+                            "expected a positive number of milliseconds",
+                        );
+                    }
+                }
+            }
+            Some(_) => self.problem(
+                format!("{}.budgets_ms", location),
+                "expected an object mapping test names to milliseconds",
+            ),
+        }
+        match exercise.get("hidden_tests") {
+            None => {}
+            Some(Value::String(path)) => self.check_hidden_tests(location, path, exercise_type),
+            Some(_) => self.problem(format!("{}.hidden_tests", location), "expected a string"),
+        }
+        match exercise.get("starter") {
+            None => {}
+            Some(Value::String(path)) => self.check_starter(location, path, exercise_type),
+            Some(_) => self.problem(format!("{}.starter", location), "expected a string"),
+        }
+        match exercise.get("protected") {
+            None => {}
+            Some(Value::Object(protected)) => {
+                for (key, hash) in protected {
+                    self.check_protected(
+                        &format!("{}.protected", location),
+                        key,
+                        hash,
+                        exercise_type,
+                    );
+                }
+            }
+            Some(_) => self.problem(
+                format!("{}.protected", location),
+                "expected an object mapping protected files to hashes",
+            ),
+        }
+    }
+
+    fn check_protected(
+        &mut self,
+        location: &str,
+        key: &str,
+        hash: &Value,
+        exercise_type: Option<ExerciseType>,
+    ) {
+        let location = format!("{}.{}", location, key);
+        if let Some(exercise_type) = exercise_type {
+            if let Err(message) = tamper::check_key(key, exercise_type) {
+                self.problem(location, message);
+                return;
+            }
+        }
+        let is_sha256 = hash.as_str().is_some_and(|hash| {
+            hash.len() == 64 && hash.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+        });
+        if !is_sha256 {
+            self.problem(
+                location,
+                "expected a SHA-256 hash (run `cargotest seal` to generate it)",
+            );
+        }
+    }
+
+    // 隐藏测试只用于 Cargo 项目，且必须存在于 hidden_tests 目录中
+    fn check_hidden_tests(
+        &mut self,
+        location: &str,
+        path: &str,
+        exercise_type: Option<ExerciseType>,
+    ) {
+        let location = format!("{}.hidden_tests", location);
+        if exercise_type == Some(ExerciseType::SingleFile) {
+            self.problem(
+                location,
+                "hidden tests are only supported for cargo_project",
+            );
+            return;
+        }
+        let full_path = self.hidden_tests_dir.join(path);
+        if !full_path.exists() {
+            self.problem(location, format!("{} does not exist", full_path.display()));
+            return;
+        }
+        // 评测器持有的 Cargo.toml 必须禁用构建脚本，否则学生的 build.rs 可以在编译前改写隐藏测试
+        let manifest = full_path.join("Cargo.toml");
+        if let Ok(content) = fs::read_to_string(&manifest) {
+            if !disables_build_script(&content) {
+                self.problem(
+                    location,
+                    format!(
+                        "{} must set `build = false` under [package]",
+                        manifest.display()
+                    ),
+                );
+            }
+        }
+    }
+
+    // 初始版本的副本必须存在于 starters 目录中，并且与习题类型相符
+    fn check_starter(&mut self, location: &str, path: &str, exercise_type: Option<ExerciseType>) {
+        let location = format!("{}.starter", location);
+        let full_path = self.starters_dir.join(path);
+        let matches_type = match exercise_type {
+            Some(ExerciseType::SingleFile) => full_path.is_file(),
+            Some(ExerciseType::CargoProject) => full_path.is_dir(),
+            None => full_path.exists(),
+        };
+        if !full_path.exists() {
+            self.problem(location, format!("{} does not exist", full_path.display()));
+        } else if !matches_type {
+            self.problem(
+                location,
+                format!("{} does not match the exercise type", full_path.display()),
+            );
+        }
+    }
+
+    // 检查习题路径存在，并且与习题类型相符
+    fn check_path(&mut self, location: &str, path: &str, exercise_type: Option<ExerciseType>) {
+        let location = format!("{}.path", location);
+        let full_path = self.exercises_dir.join(path);
+        if !full_path.exists() {
+            self.problem(location, format!("{} does not exist", full_path.display()));
+            return;
+        }
+        match exercise_type {
+            Some(ExerciseType::SingleFile) if !full_path.is_file() => self.problem(
+                location,
+                format!(
+                    "{} is not a file (type is single_file)",
+                    full_path.display()
+                ),
+            ),
+            Some(ExerciseType::CargoProject) if !full_path.join("Cargo.toml").is_file() => self
+                .problem(
+                    location,
+                    format!(
+                        "{} is not a directory with a Cargo.toml (type is cargo_project)",
+                        full_path.display()
+                    ),
+                ),
+            _ => {}
+        }
+    }
+}
+
+// Cargo.toml 的 [package] 中是否有 `build = false`
+pub fn disables_build_script(manifest: &str) -> bool {
+    let mut in_package = false;
+    for line in manifest.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_package = line == "[package]";
+        } else if in_package {
+            if let Some((key, value)) = line.split_once('=') {
+                if key.trim() == "build" && value.trim() == "false" {
+                    return true;
+                }
+            }
+        }
+    }
+    false
+}
+
+// 校验配置的结构和内容，返回发现的全部问题
+fn validate(
+    value: &Value,
+    exercises_dir: &Path,
+    hidden_tests_dir: &Path,
+    starters_dir: &Path,
+) -> Vec<ConfigProblem> {
+    let mut validator = Validator {
+        exercises_dir,
+        hidden_tests_dir,
+        starters_dir,
+        problems: Vec::new(),
+        names: HashMap::new(),
+    };
+    validator.check_config(value);
+    validator.problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn problems(value: &Value) -> Vec<(String, String)> {
+        validate(
+            value,
+            Path::new(EXERCISES_DIR),
+            Path::new(HIDDEN_TESTS_DIR),
+            Path::new(STARTERS_DIR),
+        )
+        .into_iter()
+        .map(|problem| (problem.location, problem.message))
+        .collect()
+    }
+
+    fn locations(value: &Value) -> Vec<String> {
+        problems(value)
+            .into_iter()
+            .map(|(location, _)| location)
+            .collect()
+    }
+
+    #[test]
+    fn validate_accepts_valid_config() {
+        let value = json!({
+            "timeout_secs": 60,
+            "limits": { "memory_mb": 512 },
+            "easy": [
+                { "name": "algorithm1.rs", "path": "easy/algorithm1.rs", "type": "single_file", "score": 1 }
+            ],
+            "normal": [
+                {
+                    "name": "solution1",
+                    "path": "normal/solution1",
+                    "type": "cargo_project",
+                    "score": 6,
+                    "budgets_ms": { "test_count": 1000 },
+                    "hidden_tests": "normal/solution1"
+                }
+            ],
+            "hard": []
+        });
+        assert_eq!(problems(&value), vec![]);
+    }
+
+    #[test]
+    fn disables_build_script_only_under_package() {
+        assert!(disables_build_script(
+            "[package]\nname = \"a\"\nbuild = false\n\n[dependencies]\n"
+        ));
+        assert!(!disables_build_script("[package]\nname = \"a\"\n"));
+        assert!(!disables_build_script("[package]\nbuild = \"build.rs\"\n"));
+        assert!(!disables_build_script(
+            "[package]\nname = \"a\"\n\n[dependencies]\nbuild = false\n"
+        ));
+    }
+
+    // 仓库中全部隐藏测试的 Cargo.toml 都需要禁用构建脚本
+    #[test]
+    fn hidden_test_manifests_disable_build_script() {
+        let mut files = Vec::new();
+        crate::cache::collect_files(Path::new(HIDDEN_TESTS_DIR), &mut files).unwrap();
+        let manifests: Vec<PathBuf> = files
+            .into_iter()
+            .filter(|file| file.file_name().is_some_and(|name| name == "Cargo.toml"))
+            .collect();
+        assert!(!manifests.is_empty());
+        for manifest in manifests {
+            let content = fs::read_to_string(&manifest).unwrap();
+            assert!(disables_build_script(&content), "{}", manifest.display());
+        }
+    }
+
+    #[test]
+    fn validate_rejects_non_object() {
+        assert_eq!(locations(&json!([])), vec!["$"]);
+    }
+
+    // 一次返回全部问题，而不是遇到第一个就停止
+    #[test]
+    fn validate_collects_every_problem() {
+        let value = json!({
+            "timeout_secs": 0,
+            "colour": true,
+            "limits": { "memory_mb": 0, "stack_mb": 8 },
+            "easy": [
+                { "name": "algorithm1.rs", "path": "easy/algorithm1.rs", "type": "single_file", "score": 0 },
+                {
+                    "name": "algorithm1.rs",
+                    "path": "easy/missing.rs",
+                    "type": "script",
+                    "score": 1,
+                    "budgets_ms": { "test_solve": 0 }
+                },
+                {
+                    "name": "algorithm2.rs",
+                    "path": "easy/algorithm2.rs",
+                    "type": "single_file",
+                    "score": 1,
+                    "hidden_tests": "normal/solution1",
+                    "protected": { "#[cfg(test)]": "not a hash" }
+                }
+            ],
+            "normal": "solution1"
+        });
+        assert_eq!(
+            locations(&value),
+            vec![
+                "colour",
+                "timeout_secs",
+                "limits.stack_mb",
+                "limits.memory_mb",
+                "easy[0].score",
+                "easy[1].name",
+                "easy[1].type",
+                "easy[1].path",
+                "easy[1].budgets_ms.test_solve",
+                "easy[2].hidden_tests",
+                "easy[2].protected.#[cfg(test)]",
+                "normal",
+                "hard",
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_reports_duplicate_names_with_first_location() {
+        let exercise = json!({
+            "name": "algorithm1.rs",
+            "path": "easy/algorithm1.rs",
+            "type": "single_file",
+            "score": 1
+        });
+        let value = json!({ "easy": [exercise.clone()], "normal": [], "hard": [exercise] });
+        assert_eq!(
+            problems(&value),
+            vec![(
+                "hard[0].name".to_string(),
+                "duplicate exercise name \"algorithm1.rs\" (first defined at easy[0].name)"
+                    .to_string()
+            )]
+        );
+    }
+}
@@ -0,0 +1,140 @@
+use serde::{Deserialize, Serialize};
+
+// 编译失败时最多输出的错误条数，完整内容见报告
+const MAX_PRINTED_ERRORS: usize = 3;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticLevel {
+    Error,
+    Warning,
+}
+
+// 一条编译错误或警告，位置取自主 span
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub level: DiagnosticLevel,
+    // 错误码或 lint 名，例如 E0308、clippy::needless_return
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+    // 编译器渲染好的文本（含源码片段），只用于终端输出
+    #[serde(skip)]
+    pub rendered: String,
+}
+
+// rustc --error-format=json 输出的单条诊断
+#[derive(Deserialize)]
+struct RustcDiagnostic {
+    message: String,
+    code: Option<RustcCode>,
+    level: String,
+    #[serde(default)]
+    spans: Vec<RustcSpan>,
+    rendered: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RustcCode {
+    code: String,
+}
+
+#[derive(Deserialize)]
+struct RustcSpan {
+    file_name: String,
+    line_start: usize,
+    column_start: usize,
+    is_primary: bool,
+}
+
+// cargo --message-format=json 输出中的编译器消息
+#[derive(Deserialize)]
+struct CargoMessage {
+    reason: String,
+    message: Option<RustcDiagnostic>,
+}
+
+impl RustcDiagnostic {
+    fn into_diagnostic(self) -> Option<Diagnostic> {
+        let level = match self.level.as_str() {
+            "error" | "error: internal compiler error" => DiagnosticLevel::Error,
+            "warning" => DiagnosticLevel::Warning,
+            _ => return None,
+        };
+        // "aborting due to ..."、"N warnings emitted" 之类的汇总消息不包含新信息
+        let summary =
+            self.message.starts_with("aborting due to") || self.message.ends_with("emitted");
+        if self.spans.is_empty() && self.code.is_none() && summary {
+            return None;
+        }
+        let span = self.spans.iter().find(|span| span.is_primary);
+        Some(Diagnostic {
+            level,
+            code: self.code.map(|code| code.code),
+            message: self.message,
+            file: span.map(|span| span.file_name.clone()),
+            line: span.map(|span| span.line_start),
+            column: span.map(|span| span.column_start),
+            rendered: self.rendered.unwrap_or_default(),
+        })
+    }
+}
+
+// 解析 rustc --error-format=json 写到 stderr 的诊断
+pub fn parse_rustc_diagnostics(stderr: &[u8]) -> Vec<Diagnostic> {
+    String::from_utf8_lossy(stderr)
+        .lines()
+        .filter_map(|line| serde_json::from_str::<RustcDiagnostic>(line).ok())
+        .filter_map(RustcDiagnostic::into_diagnostic)
+        .collect()
+}
+
+// 解析 cargo --message-format=json 写到 stdout 的诊断
+pub fn parse_cargo_diagnostics(stdout: &[u8]) -> Vec<Diagnostic> {
+    String::from_utf8_lossy(stdout)
+        .lines()
+        .filter_map(|line| serde_json::from_str::<CargoMessage>(line).ok())
+        .filter(|message| message.reason == "compiler-message")
+        .filter_map(|message| message.message?.into_diagnostic())
+        .collect()
+}
+
+// 将诊断拼接为编译器原本的文本输出
+pub fn render(diagnostics: &[Diagnostic]) -> String {
+    diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.rendered.as_str())
+        .collect()
+}
+
+// 终端上只展示前几条错误及其源码片段
+pub fn summarize_errors(diagnostics: &[Diagnostic]) -> Vec<String> {
+    let errors: Vec<&Diagnostic> = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.level == DiagnosticLevel::Error)
+        .collect();
+    let mut lines: Vec<String> = errors
+        .iter()
+        .take(MAX_PRINTED_ERRORS)
+        .map(|error| {
+            if error.rendered.is_empty() {
+                format!("error: {}", error.message)
+            } else {
+                error.rendered.trim_end().to_string()
+            }
+        })
+        .collect();
+    if errors.len() > MAX_PRINTED_ERRORS {
+        lines.push(format!(
+            "... and {} more error(s), see the report for details",
+            errors.len() - MAX_PRINTED_ERRORS
+        ));
+    }
+    lines
+}
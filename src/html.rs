@@ -0,0 +1,177 @@
+use crate::libtest::TestStatus;
+use crate::{Difficulty, ExerciseResult, ExerciseStatus, Report};
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const STYLE: &str = "
+body { font-family: -apple-system, 'Segoe UI', Helvetica, Arial, sans-serif; margin: 2em auto; max-width: 1100px; color: #24292f; }
+h1, h2 { font-weight: 600; }
+table { border-collapse: collapse; width: 100%; margin-bottom: 1.5em; }
+th, td { border: 1px solid #d0d7de; padding: 6px 10px; text-align: left; vertical-align: top; }
+th { background: #f6f8fa; }
+td.num { text-align: right; }
+.badge { display: inline-block; padding: 1px 8px; border-radius: 10px; color: #fff; font-size: 0.85em; }
+.passed { background: #1a7f37; }
+.failed { background: #cf222e; }
+.timed_out { background: #9a6700; }
+.ignored { background: #6e7781; }
+.in_progress { background: #0969da; }
+.memory_limit_exceeded, .cpu_limit_exceeded, .process_limit_exceeded, .file_size_limit_exceeded { background: #8250df; }
+.tampered { background: #1f2328; }
+details { margin: 4px 0; }
+summary { cursor: pointer; }
+pre { background: #f6f8fa; padding: 8px; overflow-x: auto; white-space: pre-wrap; font-size: 0.85em; }
+";
+
+// 将评测报告保存为单个静态 HTML 文件，不依赖任何外部资源
+pub fn save_report_to_html(path: &Path, report: &Report) -> io::Result<()> {
+    fs::write(path, render_report(report))
+}
+
+fn render_report(report: &Report) -> String {
+    let statistics = &report.statistics;
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>cargotest report</title>\n<style>{}</style>\n</head>\n<body>\n",
+        STYLE
+    );
+    html.push_str("<h1>Evaluation Report</h1>\n");
+    let _ = writeln!(
+        html,
+        "<p>Total score: <strong>{}</strong> &middot; Passed: {} / {} &middot; Timeouts: {} &middot; In progress: {} &middot; Total time: {}s</p>",
+        statistics.total_score,
+        statistics.total_successes,
+        statistics.total_exercises,
+        statistics.total_timeouts,
+        statistics.total_in_progress,
+        statistics.total_time
+    );
+
+    render_score_table(&mut html, report);
+
+    html.push_str("<h2>Exercises</h2>\n<table>\n<tr><th>Exercise</th><th>Difficulty</th><th>Status</th><th>Score</th><th>Time</th><th>Details</th></tr>\n");
+    for exercise in &report.exercises {
+        render_exercise(&mut html, exercise);
+    }
+    html.push_str("</table>\n</body>\n</html>\n");
+    html
+}
+
+// 按难度汇总的得分表
+fn render_score_table(html: &mut String, report: &Report) {
+    html.push_str(
+        "<h2>Scores</h2>\n<table>\n<tr><th>Difficulty</th><th>Passed</th><th>Score</th></tr>\n",
+    );
+    for difficulty in Difficulty::ALL {
+        let exercises: Vec<&ExerciseResult> = report
+            .exercises
+            .iter()
+            .filter(|exercise| exercise.difficulty == difficulty)
+            .collect();
+        if exercises.is_empty() {
+            continue;
+        }
+        let passed = exercises.iter().filter(|exercise| exercise.result).count();
+        let score: f64 = exercises.iter().map(|exercise| exercise.score).sum();
+        let max_score: i32 = exercises.iter().map(|exercise| exercise.max_score).sum();
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td class=\"num\">{} / {}</td><td class=\"num\">{} / {}</td></tr>",
+            difficulty.as_str(),
+            passed,
+            exercises.len(),
+            (score * 100.0).round() / 100.0,
+            max_score
+        );
+    }
+    html.push_str("</table>\n");
+}
+
+fn render_exercise(html: &mut String, exercise: &ExerciseResult) {
+    let (class, label) = status_badge(exercise.status);
+    let _ = write!(
+        html,
+        "<tr><td>{}</td><td>{}</td><td><span class=\"badge {}\">{}</span></td><td class=\"num\">{} / {}</td><td class=\"num\">{:.2}s</td><td>",
+        escape(&exercise.name),
+        exercise.difficulty.as_str(),
+        class,
+        label,
+        exercise.score,
+        exercise.max_score,
+        exercise.duration
+    );
+
+    if !exercise.compiler_output.is_empty() {
+        let _ = write!(
+            html,
+            "<details><summary>Compiler output</summary><pre>{}</pre></details>",
+            escape(&exercise.compiler_output)
+        );
+    }
+
+    if !exercise.tests.is_empty() {
+        let failed = exercise
+            .tests
+            .iter()
+            .filter(|test| test.status == TestStatus::Failed)
+            .count();
+        let _ = write!(
+            html,
+            "<details{}><summary>Tests ({} / {} passed)</summary><table>",
+            if failed > 0 { " open" } else { "" },
+            exercise.tests.len() - failed,
+            exercise.tests.len()
+        );
+        for test in &exercise.tests {
+            let (class, label) = match test.status {
+                TestStatus::Ok => ("passed", "ok"),
+                TestStatus::Failed => ("failed", "failed"),
+                TestStatus::Ignored => ("ignored", "ignored"),
+            };
+            let _ = write!(
+                html,
+                "<tr><td>{}</td><td><span class=\"badge {}\">{}</span></td><td class=\"num\">{}</td><td>",
+                escape(&test.name),
+                class,
+                label,
+                test.duration
+                    .map(|duration| format!("{:.3}s", duration))
+                    .unwrap_or_default()
+            );
+            if !test.stdout.is_empty() {
+                let _ = write!(
+                    html,
+                    "<details><summary>Output</summary><pre>{}</pre></details>",
+                    escape(&test.stdout)
+                );
+            }
+            html.push_str("</td></tr>");
+        }
+        html.push_str("</table></details>");
+    }
+
+    html.push_str("</td></tr>\n");
+}
+
+// 徽章的 CSS 类名与状态名一致
+fn status_badge(status: ExerciseStatus) -> (&'static str, &'static str) {
+    (status.as_str(), status.label())
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,136 @@
+use crate::libtest::TestStatus;
+use crate::{ExerciseResult, ExerciseStatus, Report};
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// 将评测报告保存为 JUnit XML，每道习题对应一个 testsuite，每个测试用例对应一个 testcase
+pub fn save_report_to_junit(path: &Path, report: &Report) -> io::Result<()> {
+    fs::write(path, render_report(report))
+}
+
+// 单个 testsuite 的 XML 及其用例计数
+struct Suite {
+    xml: String,
+    tests: usize,
+    failures: usize,
+}
+
+fn render_report(report: &Report) -> String {
+    let suites: Vec<Suite> = report.exercises.iter().map(render_suite).collect();
+    let tests: usize = suites.iter().map(|suite| suite.tests).sum();
+    let failures: usize = suites.iter().map(|suite| suite.failures).sum();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<testsuites name=\"cargotest\" tests=\"{}\" failures=\"{}\" time=\"{}\">",
+        tests, failures, report.statistics.total_time
+    );
+    for suite in suites {
+        xml.push_str(&suite.xml);
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+fn render_suite(exercise: &ExerciseResult) -> Suite {
+    let mut cases = String::new();
+    let mut tests = 0;
+    let mut failures = 0;
+    let mut skipped = 0;
+
+    for test in &exercise.tests {
+        tests += 1;
+        let _ = write!(
+            cases,
+            "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.6}\"",
+            escape(&test.name),
+            escape(&exercise.name),
+            test.duration.unwrap_or(0.0)
+        );
+        match test.status {
+            TestStatus::Ok => cases.push_str("/>\n"),
+            TestStatus::Ignored => {
+                skipped += 1;
+                cases.push_str(">\n      <skipped/>\n    </testcase>\n");
+            }
+            TestStatus::Failed => {
+                failures += 1;
+                let _ = write!(
+                    cases,
+                    ">\n      <failure message=\"test failed\">{}</failure>\n    </testcase>\n",
+                    escape(&test.stdout)
+                );
+            }
+        }
+    }
+
+    // 编译失败、clippy 未通过或超时等情况没有对应的失败用例，单独记为一个 testcase
+    let test_failed = exercise
+        .tests
+        .iter()
+        .any(|test| test.status == TestStatus::Failed);
+    if exercise.status == ExerciseStatus::InProgress {
+        // 标记为 I AM NOT DONE 的习题未被评测，记为跳过
+        tests += 1;
+        skipped += 1;
+        let _ = write!(
+            cases,
+            "    <testcase name=\"{}\" classname=\"{}\" time=\"0\">\n      <skipped message=\"in progress\"/>\n    </testcase>\n",
+            escape(&exercise.name),
+            escape(&exercise.name)
+        );
+    } else if !exercise.result && !test_failed {
+        tests += 1;
+        failures += 1;
+        let message = match exercise.status {
+            ExerciseStatus::TimedOut => "timed out",
+            ExerciseStatus::Tampered => "protected files were modified",
+            _ => "evaluation failed",
+        };
+        let _ = write!(
+            cases,
+            "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.6}\">\n      <failure message=\"{}\">{}</failure>\n    </testcase>\n",
+            escape(&exercise.name),
+            escape(&exercise.name),
+            exercise.duration,
+            message,
+            escape(&exercise.compiler_output)
+        );
+    }
+
+    let xml = format!(
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{:.6}\">\n{}  </testsuite>\n",
+        escape(&exercise.name),
+        tests,
+        failures,
+        skipped,
+        exercise.duration,
+        cases
+    );
+    Suite {
+        xml,
+        tests,
+        failures,
+    }
+}
+
+// 转义 XML 特殊字符，并去掉 XML 1.0 不允许出现的控制字符
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
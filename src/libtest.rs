@@ -0,0 +1,176 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// 单个测试用例的结论
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TestStatus {
+    Ok,
+    Failed,
+    Ignored,
+}
+
+// 单个测试用例的结果
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TestCase {
+    pub name: String,
+    pub status: TestStatus,
+    // 用例耗时（秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration: Option<f64>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub stdout: String,
+}
+
+// libtest 以 JSON 格式输出的单条事件
+#[derive(Deserialize)]
+struct TestEvent {
+    #[serde(rename = "type")]
+    kind: String,
+    event: String,
+    name: Option<String>,
+    exec_time: Option<f64>,
+    stdout: Option<String>,
+}
+
+// cargo --message-format=json 输出的构建产物信息
+#[derive(Deserialize)]
+struct CargoArtifact {
+    reason: String,
+    profile: Option<CargoProfile>,
+    executable: Option<PathBuf>,
+}
+
+#[derive(Deserialize)]
+struct CargoProfile {
+    test: bool,
+}
+
+// 构造运行测试二进制文件的命令，要求 libtest 以 JSON 格式逐条输出用例结果
+//
+// JSON 格式在稳定版工具链上属于不稳定选项，因此只对测试进程设置
+// RUSTC_BOOTSTRAP，不影响习题本身的编译。
+pub fn test_binary_command(test_binary: &Path) -> Command {
+    let mut command = Command::new(test_binary);
+    command
+        .args(["-Z", "unstable-options", "--format", "json"])
+        .args(["--report-time", "--show-output"])
+        .env("RUSTC_BOOTSTRAP", "1");
+    command
+}
+
+// 解析测试二进制文件的输出，返回已结束的测试用例
+pub fn parse_test_events(stdout: &[u8]) -> Vec<TestCase> {
+    String::from_utf8_lossy(stdout)
+        .lines()
+        .filter_map(|line| serde_json::from_str::<TestEvent>(line).ok())
+        .filter(|event| event.kind == "test")
+        .filter_map(|event| {
+            let status = match event.event.as_str() {
+                "ok" => TestStatus::Ok,
+                "failed" | "timeout" => TestStatus::Failed,
+                "ignored" => TestStatus::Ignored,
+                _ => return None,
+            };
+            Some(TestCase {
+                name: event.name?,
+                status,
+                duration: event.exec_time,
+                stdout: event.stdout.unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+// 从 `cargo test --no-run --message-format=json` 的输出中找出全部测试二进制文件
+pub fn parse_test_executables(stdout: &[u8]) -> Vec<PathBuf> {
+    String::from_utf8_lossy(stdout)
+        .lines()
+        .filter_map(|line| serde_json::from_str::<CargoArtifact>(line).ok())
+        .filter(|artifact| artifact.reason == "compiler-artifact")
+        .filter(|artifact| {
+            artifact
+                .profile
+                .as_ref()
+                .is_some_and(|profile| profile.test)
+        })
+        .filter_map(|artifact| artifact.executable)
+        .collect()
+}
+
+// 源码中以 #[test] 标记的测试函数名
+pub fn declared_tests(source: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut after_attribute = false;
+    for line in source.lines().map(str::trim) {
+        if line == "#[test]" {
+            after_attribute = true;
+        } else if after_attribute {
+            // 跳过 #[test] 与函数之间的其他属性和注释
+            if let Some(rest) = line.split_once("fn ").map(|(_, rest)| rest) {
+                let name: String = rest
+                    .chars()
+                    .take_while(|c| c.is_alphanumeric() || *c == '_')
+                    .collect();
+                names.push(name);
+                after_attribute = false;
+            }
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_test_events_keeps_finished_tests() {
+        let stdout = br#"{ "type": "suite", "event": "started", "test_count": 4 }
+{ "type": "test", "event": "started", "name": "tests::a" }
+{ "type": "test", "name": "tests::a", "event": "ok", "exec_time": 0.5, "stdout": "Total score: 100.00\n" }
+{ "type": "test", "name": "tests::b", "event": "failed", "exec_time": 0.1 }
+{ "type": "test", "name": "tests::c", "event": "timeout" }
+{ "type": "test", "name": "tests::d", "event": "ignored" }
+running 4 tests
+{ "type": "suite", "event": "failed", "passed": 1, "failed": 2, "ignored": 1 }
+"#;
+        let tests = parse_test_events(stdout);
+        assert_eq!(
+            tests,
+            vec![
+                TestCase {
+                    name: "tests::a".to_string(),
+                    status: TestStatus::Ok,
+                    duration: Some(0.5),
+                    stdout: "Total score: 100.00\n".to_string(),
+                },
+                TestCase {
+                    name: "tests::b".to_string(),
+                    status: TestStatus::Failed,
+                    duration: Some(0.1),
+                    stdout: String::new(),
+                },
+                TestCase {
+                    name: "tests::c".to_string(),
+                    status: TestStatus::Failed,
+                    duration: None,
+                    stdout: String::new(),
+                },
+                TestCase {
+                    name: "tests::d".to_string(),
+                    status: TestStatus::Ignored,
+                    duration: None,
+                    stdout: String::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_test_events_ignores_non_json_output() {
+        assert!(parse_test_events(b"").is_empty());
+        assert!(parse_test_events(b"error: could not compile\nnot json {").is_empty());
+    }
+}
@@ -0,0 +1,100 @@
+use log::{Level, LevelFilter};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// log4rs 配置文件，决定日志记录写入哪个滚动日志文件
+pub const LOG_CONFIG_FILE: &str = "log4rs.yaml";
+
+// 日志记录中习题名和评测步骤对应的 MDC 键，可在配置文件的 pattern 中用 {X(exercise)} 引用
+const EXERCISE_KEY: &str = "exercise";
+const PHASE_KEY: &str = "phase";
+
+// 终端输出的详细程度，由 -q / -v 控制
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Verbosity {
+    // 只输出警告、错误和评测摘要
+    Quiet,
+    #[default]
+    Normal,
+    // 额外输出执行的命令和清理等细节
+    Verbose,
+}
+
+impl Verbosity {
+    fn console_level(self) -> LevelFilter {
+        match self {
+            Verbosity::Quiet => LevelFilter::Warn,
+            Verbosity::Normal => LevelFilter::Info,
+            Verbosity::Verbose => LevelFilter::Debug,
+        }
+    }
+}
+
+static CONSOLE_LEVEL: AtomicUsize = AtomicUsize::new(LevelFilter::Info as usize);
+
+// 读取 log4rs 配置并设置终端输出的详细程度；配置文件不存在时不写日志文件
+pub fn init(verbosity: Verbosity) {
+    CONSOLE_LEVEL.store(verbosity.console_level() as usize, Ordering::Relaxed);
+    if !Path::new(LOG_CONFIG_FILE).exists() {
+        return;
+    }
+    if let Err(e) = log4rs::init_file(LOG_CONFIG_FILE, Default::default()) {
+        eprintln!("Failed to load {}: {}", LOG_CONFIG_FILE, e);
+    }
+}
+
+// 该级别的输出是否显示在终端上
+pub fn console_enabled(level: Level) -> bool {
+    level as usize <= CONSOLE_LEVEL.load(Ordering::Relaxed)
+}
+
+// 之后在当前线程中产生的日志记录都带有该评测步骤
+pub fn set_phase(phase: &str) {
+    log_mdc::insert(PHASE_KEY, phase);
+}
+
+// 在指定步骤中执行 f，期间产生的日志记录带有该步骤
+pub fn in_phase<T>(phase: &str, f: impl FnOnce() -> T) -> T {
+    set_phase(phase);
+    let result = f();
+    log_mdc::remove(PHASE_KEY);
+    result
+}
+
+// 评测单道习题期间为当前线程的日志记录附加习题名，离开作用域时移除
+pub struct ExerciseScope;
+
+impl ExerciseScope {
+    pub fn enter(name: &str) -> Self {
+        log_mdc::insert(EXERCISE_KEY, name);
+        ExerciseScope
+    }
+}
+
+impl Drop for ExerciseScope {
+    fn drop(&mut self) {
+        log_mdc::remove(EXERCISE_KEY);
+        log_mdc::remove(PHASE_KEY);
+    }
+}
+
+// 去除输出中的 ANSI 转义序列，日志文件和 TUI 只保存纯文本
+pub fn strip_ansi(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // CSI 序列以 0x40..=0x7e 范围内的字符结束
+            if chars.next() == Some('[') {
+                for c in chars.by_ref() {
+                    if ('\x40'..='\x7e').contains(&c) {
+                        break;
+                    }
+                }
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
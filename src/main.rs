@@ -1,31 +1,298 @@
+mod cache;
+mod cli;
+mod command;
+mod config;
+mod diagnostics;
+mod html;
+mod junit;
+mod libtest;
+mod logging;
+mod reset;
+mod sandbox;
+mod scaffold;
+mod selection;
+mod state;
+mod style;
+mod subcommands;
+mod tamper;
+mod timing;
+mod tui;
+mod watch;
+
+use cache::BuildCache;
+use cli::{Options, Subcommand};
+use command::{run_with_deadline, CommandOutcome};
+use config::{load_exercise_config, Difficulty, Exercise, ExerciseConfig, ExerciseType};
+use diagnostics::Diagnostic;
+use libtest::{TestCase, TestStatus};
+use log::Level;
+use sandbox::{PrivateDir, ResourceLimits};
+use selection::Selection;
 use serde::{Deserialize, Serialize};
+use state::{ProgressState, STATE_FILE};
+use std::collections::BTreeMap;
 use std::fs::{self, File};
-use std::io::{self, Write};
+use std::io;
 use std::path::{Path, PathBuf};
 use std::process::{exit, Command};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
 use std::time::Instant;
+use timing::{Measurement, TestTiming};
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-struct Exercise {
-    name: String,
-    path: String,
-    #[serde(rename = "type")]
-    exercise_type: String,
-    score: i32,
+// 习题所在目录
+const EXERCISES_DIR: &str = "./exercises";
+
+// grader 持有的隐藏测试所在目录，学生不应修改
+const HIDDEN_TESTS_DIR: &str = "./hidden_tests";
+
+// 隐藏测试为单个文件时，在项目副本中替换的测试文件
+const HIDDEN_TESTS_TARGET: &str = "src/tests.rs";
+
+// 习题初始版本的副本所在目录，供 reset 恢复
+const STARTERS_DIR: &str = "./starters";
+
+// reset 前备份学生当前作答的目录
+const BACKUPS_DIR: &str = ".cargotest/backups";
+
+const CONFIG_FILE: &str = "exercise_config.json";
+
+const REPORT_FILE: &str = "report.json";
+
+// --shared-target 模式下所有 Cargo 习题共用的构建目录，评测结束后保留
+const SHARED_TARGET_DIR: &str = ".cargotest/target";
+
+// 退出码：评测未通过，例如有习题失败、总分低于 --fail-under，或 verify 的习题未通过
+const EXIT_FAILURE: i32 = 1;
+
+// 退出码：配置文件无法读取或校验未通过（2 为命令行用法错误，见 cli::EXIT_USAGE）
+const EXIT_CONFIG: i32 = 3;
+
+// 退出码：评测未能完成，例如筛选不到习题或无法读取进度文件
+const EXIT_ERROR: i32 = 4;
+
+// 评测过程中各习题共用的设置与状态
+struct EvaluationContext {
+    cache: BuildCache,
+    // 共用的 CARGO_TARGET_DIR，为 None 时各项目单独构建并在评测后清理 target 目录
+    shared_target_dir: Option<PathBuf>,
+    // 运行测试二进制文件时的资源限制
+    limits: ResourceLimits,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-struct ExerciseConfig {
-    easy: Vec<Exercise>,
-    normal: Vec<Exercise>,
-    hard: Vec<Exercise>,
+impl EvaluationContext {
+    fn new(options: &Options, config: &ExerciseConfig) -> Self {
+        // cargo 在各项目目录中运行，需要使用绝对路径
+        let shared_target_dir = options
+            .shared_target
+            .then(|| std::env::current_dir().map(|dir| dir.join(SHARED_TARGET_DIR)))
+            .and_then(Result::ok);
+        EvaluationContext {
+            cache: BuildCache::load(!options.no_cache),
+            shared_target_dir,
+            limits: config.limits,
+        }
+    }
+
+    // 单独评测一道习题时使用：不读写缓存，构建后清理 target 目录
+    fn standalone(config: &ExerciseConfig) -> Self {
+        EvaluationContext {
+            cache: BuildCache::disabled(),
+            shared_target_dir: None,
+            limits: config.limits,
+        }
+    }
+}
+
+// 运行习题测试二进制文件时使用的设置
+struct TestSettings<'a> {
+    limits: &'a ResourceLimits,
+    // 测试名到时间预算（毫秒）的映射
+    budgets_ms: &'a BTreeMap<String, u64>,
+    repetitions: u32,
+    // 覆盖到项目副本中的隐藏测试
+    hidden_tests: Option<PathBuf>,
+}
+
+// 单道习题的评测结论
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum ExerciseStatus {
+    Passed,
+    Failed,
+    TimedOut,
+    // 源文件中仍带有 "I AM NOT DONE" 标记，未评测
+    InProgress,
+    // 测试进程超出了资源限制
+    MemoryLimitExceeded,
+    CpuLimitExceeded,
+    ProcessLimitExceeded,
+    FileSizeLimitExceeded,
+    // 受保护的文件被修改，未评测
+    Tampered,
+}
+
+impl ExerciseStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ExerciseStatus::Passed => "passed",
+            ExerciseStatus::Failed => "failed",
+            ExerciseStatus::TimedOut => "timed_out",
+            ExerciseStatus::InProgress => "in_progress",
+            ExerciseStatus::MemoryLimitExceeded => "memory_limit_exceeded",
+            ExerciseStatus::CpuLimitExceeded => "cpu_limit_exceeded",
+            ExerciseStatus::ProcessLimitExceeded => "process_limit_exceeded",
+            ExerciseStatus::FileSizeLimitExceeded => "file_size_limit_exceeded",
+            ExerciseStatus::Tampered => "tampered",
+        }
+    }
+
+    // 终端输出和 HTML 报告中使用的大写标签
+    fn label(&self) -> &'static str {
+        match self {
+            ExerciseStatus::Passed => "PASSED",
+            ExerciseStatus::Failed => "FAILED",
+            ExerciseStatus::TimedOut => "TIMED OUT",
+            ExerciseStatus::InProgress => "IN PROGRESS",
+            ExerciseStatus::MemoryLimitExceeded => "MEMORY LIMIT EXCEEDED",
+            ExerciseStatus::CpuLimitExceeded => "CPU LIMIT EXCEEDED",
+            ExerciseStatus::ProcessLimitExceeded => "PROCESS LIMIT EXCEEDED",
+            ExerciseStatus::FileSizeLimitExceeded => "FILE SIZE LIMIT EXCEEDED",
+            ExerciseStatus::Tampered => "TAMPERED",
+        }
+    }
+}
+
+// 单道习题的评测结果
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Evaluation {
+    status: ExerciseStatus,
+    // 测试输出中解析到的 "Total score"（百分制）
+    test_score: Option<f64>,
+    tests: Vec<TestCase>,
+    // 编译、构建或 clippy 失败时的错误输出
+    compiler_output: String,
+    // 编译与 clippy 产生的错误和警告
+    #[serde(default)]
+    diagnostics: Vec<Diagnostic>,
+    // 有时间预算的测试的计时结果
+    #[serde(default)]
+    timings: Vec<TestTiming>,
+    // 内容与配置中的哈希不一致的受保护文件
+    #[serde(default)]
+    tampered: Vec<String>,
+    // 评测耗时（秒）
+    duration: f64,
+    // 是否直接复用了缓存中的结果
+    #[serde(skip)]
+    cached: bool,
+}
+
+impl Evaluation {
+    fn new(status: ExerciseStatus) -> Self {
+        Evaluation {
+            status,
+            test_score: None,
+            tests: Vec::new(),
+            compiler_output: String::new(),
+            diagnostics: Vec::new(),
+            timings: Vec::new(),
+            tampered: Vec::new(),
+            duration: 0.0,
+            cached: false,
+        }
+    }
+
+    // 记录编译诊断，build、test 与 clippy 重复报告的诊断只保留一条，返回新增的诊断
+    fn add_diagnostics(&mut self, diagnostics: Vec<Diagnostic>) -> &[Diagnostic] {
+        let first_new = self.diagnostics.len();
+        for diagnostic in diagnostics {
+            let duplicate = self.diagnostics.iter().any(|existing| {
+                (existing.level, &existing.code, &existing.message)
+                    == (diagnostic.level, &diagnostic.code, &diagnostic.message)
+                    && (&existing.file, existing.line, existing.column)
+                        == (&diagnostic.file, diagnostic.line, diagnostic.column)
+            });
+            if !duplicate {
+                self.diagnostics.push(diagnostic);
+            }
+        }
+        &self.diagnostics[first_new..]
+    }
+
+    // 记录编译失败：保存诊断及其文本，并在终端展示前几条错误
+    //
+    // stderr 中的 JSON 诊断已包含在 diagnostics 中，只追加其余的文本行
+    // （例如 cargo 的 "could not compile"）。
+    fn add_compile_failure(
+        &mut self,
+        diagnostics: Vec<Diagnostic>,
+        stderr: &[u8],
+        output: &mut ExerciseOutput,
+    ) {
+        let added = self.add_diagnostics(diagnostics);
+        for line in diagnostics::summarize_errors(added) {
+            output.error(line);
+        }
+        let mut text = diagnostics::render(added);
+        for line in String::from_utf8_lossy(stderr).lines() {
+            if !line.starts_with('{') {
+                text.push_str(line);
+                text.push('\n');
+            }
+        }
+        self.compiler_output.push_str(&text);
+    }
+
+    fn passed(&self) -> bool {
+        self.status == ExerciseStatus::Passed
+    }
+
+    // 计算习题得分，partial_credit 关闭时只有通过才得分
+    fn score(&self, exercise: &Exercise, partial_credit: bool) -> f64 {
+        match (self.status, self.test_score) {
+            (ExerciseStatus::Passed, _) => exercise.score as f64,
+            // 超出时间预算时不给部分分
+            (ExerciseStatus::Failed, Some(test_score))
+                if partial_credit && self.timings.iter().all(TestTiming::within_budget) =>
+            {
+                round_score(exercise.score as f64 * test_score.clamp(0.0, 100.0) / 100.0)
+            }
+            _ => 0.0,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 struct ExerciseResult {
     name: String,
+    #[serde(default)]
+    difficulty: Difficulty,
     result: bool,
-    score: i32,
+    status: ExerciseStatus,
+    score: f64,
+    // 习题满分
+    #[serde(default)]
+    max_score: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    test_score: Option<f64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tests: Vec<TestCase>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    compiler_output: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    diagnostics: Vec<Diagnostic>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    timings: Vec<TestTiming>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tampered: Vec<String>,
+    // 评测耗时（秒）
+    #[serde(default)]
+    duration: f64,
+    // 结果来自缓存，本次未重新编译
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    cached: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -33,8 +300,15 @@ struct Statistics {
     total_exercises: usize,
     total_successes: usize,
     total_failures: usize,
-    total_score: i32,
+    #[serde(default)]
+    total_timeouts: usize,
+    #[serde(default)]
+    total_in_progress: usize,
+    total_score: f64,
     total_time: u64,
+    // 只评测了部分习题时记录筛选条件
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    selection: Option<Selection>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -43,21 +317,167 @@ struct Report {
     statistics: Statistics,
 }
 
+// 评测进行到的步骤，写入日志记录的 phase 字段，并供 TUI 显示实时状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Building,
+    Testing,
+    Linting,
+    Cleanup,
+}
+
+impl Phase {
+    // 日志记录中的 phase 字段
+    fn as_str(&self) -> &'static str {
+        match self {
+            Phase::Building => "compile",
+            Phase::Testing => "test",
+            Phase::Linting => "clippy",
+            Phase::Cleanup => "cleanup",
+        }
+    }
+
+    // TUI 中显示的状态
+    fn label(&self) -> &'static str {
+        match self {
+            Phase::Building => "building",
+            Phase::Testing => "testing",
+            Phase::Linting => "clippy",
+            Phase::Cleanup => "cleanup",
+        }
+    }
+}
+
+// 评测过程中的实时事件，第一个字段为习题在列表中的位置
+#[derive(Debug)]
+enum LiveEvent {
+    Phase(usize, Phase),
+    Line(usize, String),
+    Finished(usize, Box<Evaluation>),
+}
+
+// 单道习题评测过程中的输出，同时写入日志，评测结束后按 -q / -v 设置的级别统一打印
+#[derive(Debug, Default)]
+struct ExerciseOutput {
+    lines: Vec<(Level, String)>,
+    // TUI 模式下同时把输出和评测步骤实时发送出去
+    live: Option<(usize, mpsc::Sender<LiveEvent>)>,
+}
+
+impl ExerciseOutput {
+    fn live(index: usize, sender: mpsc::Sender<LiveEvent>) -> Self {
+        ExerciseOutput {
+            lines: Vec::new(),
+            live: Some((index, sender)),
+        }
+    }
+
+    // 执行的命令、清理结果等细节，只在 -v 时打印
+    fn debug(&mut self, line: String) {
+        self.push(Level::Debug, line);
+    }
+
+    fn info(&mut self, line: String) {
+        self.push(Level::Info, line);
+    }
+
+    // 未通过的结论，-q 时仍会打印
+    fn warn(&mut self, line: String) {
+        self.push(Level::Warn, line);
+    }
+
+    // 编译错误和评测器自身的错误，打印到 stderr
+    fn error(&mut self, line: String) {
+        self.push(Level::Error, line);
+    }
+
+    fn push(&mut self, level: Level, line: String) {
+        log::log!(
+            level,
+            "{}",
+            logging::strip_ansi(line.trim_start_matches('\n'))
+        );
+        self.send(|index| LiveEvent::Line(index, line.clone()));
+        self.lines.push((level, line));
+    }
+
+    fn phase(&mut self, phase: Phase) {
+        logging::set_phase(phase.as_str());
+        self.send(|index| LiveEvent::Phase(index, phase));
+    }
+
+    fn send(&self, event: impl FnOnce(usize) -> LiveEvent) {
+        if let Some((index, sender)) = &self.live {
+            let _ = sender.send(event(*index));
+        }
+    }
+
+    fn flush(self) {
+        for (level, line) in self.lines {
+            if !logging::console_enabled(level) {
+                continue;
+            }
+            if level == Level::Error {
+                eprintln!("{}", line);
+            } else {
+                println!("{}", line);
+            }
+        }
+    }
+}
+
 fn main() {
-    let args: Vec<String> = std::env::args().collect();
-    if args.len() < 2 {
-        eprintln!("Please provide a command: 'watch' or 'all'");
-        exit(1);
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.is_empty() {
+        eprint!("{}", cli::USAGE);
+        exit(cli::EXIT_USAGE);
     }
 
-    let mode = &args[1];
+    let (command, global) = match cli::parse_args(&args) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("{}\n", e);
+            eprint!("{}", cli::USAGE);
+            exit(cli::EXIT_USAGE);
+        }
+    };
+    logging::init(global.verbosity);
+    style::init(global.color);
+
+    let code = match command {
+        Subcommand::Run(options) => run_exercises("run", &options),
+        Subcommand::Watch(options) => run_exercises("watch", &options),
+        Subcommand::Tui(options) => run_exercises("tui", &options),
+        Subcommand::List => subcommands::list_exercises(),
+        Subcommand::Verify(name) => subcommands::verify_exercise(&name),
+        Subcommand::Hint(name) => subcommands::show_hint(&name),
+        Subcommand::Report(path) => subcommands::print_report(&path),
+        Subcommand::Seal => subcommands::seal_protected_files(),
+        Subcommand::New(options) => subcommands::new_exercise(&options),
+        Subcommand::Reset(name, yes) => subcommands::reset_exercise(&name, yes),
+        Subcommand::Help(topic) => match cli::help_text(topic.as_deref()) {
+            Some(text) => {
+                print!("{}", text);
+                0
+            }
+            None => {
+                eprintln!("Unknown command: {}", topic.unwrap_or_default());
+                cli::EXIT_USAGE
+            }
+        },
+    };
+    exit(code);
+}
+
+// 评测习题（run 和 watch 子命令），并保存评测报告
+fn run_exercises(mode: &str, options: &Options) -> i32 {
     let start_time = Instant::now();
 
-    let config = match load_exercise_config("exercise_config.json") {
+    let config = match load_exercise_config(CONFIG_FILE) {
         Ok(cfg) => cfg,
         Err(e) => {
             eprintln!("Failed to load config file: {}", e);
-            exit(1);
+            return EXIT_CONFIG;
         }
     };
 
@@ -67,191 +487,848 @@ fn main() {
             total_exercises: 0,
             total_successes: 0,
             total_failures: 0,
-            total_score: 0,
+            total_timeouts: 0,
+            total_in_progress: 0,
+            total_score: 0.0,
             total_time: 0,
+            selection: None,
         },
     };
 
-    evaluate_exercises_from_config(mode, config, &mut report);
+    if let Err(e) = evaluate_exercises_from_config(mode, options, config, &mut report) {
+        eprintln!("{}", e);
+        return EXIT_ERROR;
+    }
 
     report.statistics.total_time = start_time.elapsed().as_secs();
-    report.statistics.total_exercises =
-        report.statistics.total_successes + report.statistics.total_failures;
+    report.statistics.total_exercises = report.statistics.total_successes
+        + report.statistics.total_failures
+        + report.statistics.total_in_progress;
 
     println!("\nSummary:");
+    if let Some(selection) = &report.statistics.selection {
+        println!("Selection: {}", describe_selection(selection));
+    }
     println!("Total exercises: {}", report.statistics.total_exercises);
     println!("Total successes: {}", report.statistics.total_successes);
     println!("Total failures: {}", report.statistics.total_failures);
+    println!("Total timeouts: {}", report.statistics.total_timeouts);
+    println!("Total in progress: {}", report.statistics.total_in_progress);
     println!("Total score: {}", report.statistics.total_score);
 
-    if let Err(e) = save_report_to_json("report.json", &report) {
+    if let Err(e) = save_report_to_json(REPORT_FILE, &report) {
         eprintln!("Error saving report: {}", e);
     }
+
+    if let Err(e) = html::save_report_to_html(Path::new("report.html"), &report) {
+        eprintln!("Error saving HTML report: {}", e);
+    }
+
+    if let Some(junit_path) = &options.junit {
+        if let Err(e) = junit::save_report_to_junit(junit_path, &report) {
+            eprintln!("Error saving JUnit report: {}", e);
+        }
+    }
+
+    grading_exit_code(&report, options)
 }
 
-fn load_exercise_config(file_path: &str) -> Result<ExerciseConfig, io::Error> {
-    let file = File::open(file_path)?;
-    let config: ExerciseConfig = serde_json::from_reader(file)?;
-    Ok(config)
+// 有习题未通过，或总分低于 --fail-under / --fail-under-percent 时返回 EXIT_FAILURE
+fn grading_exit_code(report: &Report, options: &Options) -> i32 {
+    let statistics = &report.statistics;
+    let mut code = 0;
+    if statistics.total_failures > 0 {
+        code = EXIT_FAILURE;
+    }
+    if let Some(threshold) = options.fail_under {
+        if statistics.total_score < threshold {
+            eprintln!(
+                "Total score {} is below --fail-under {}",
+                statistics.total_score, threshold
+            );
+            code = EXIT_FAILURE;
+        }
+    }
+    if let Some(threshold) = options.fail_under_percent {
+        let max_score: i32 = report.exercises.iter().map(|e| e.max_score).sum();
+        let percent = if max_score > 0 {
+            round_score(statistics.total_score * 100.0 / max_score as f64)
+        } else {
+            100.0
+        };
+        if percent < threshold {
+            eprintln!(
+                "Total score {} ({}% of {}) is below --fail-under-percent {}",
+                statistics.total_score, percent, max_score, threshold
+            );
+            code = EXIT_FAILURE;
+        }
+    }
+    code
 }
 
-fn evaluate_exercises_from_config(mode: &str, config: ExerciseConfig, report: &mut Report) {
-    let all_exercises = [config.easy, config.normal, config.hard].concat();
+fn evaluate_exercises_from_config(
+    mode: &str,
+    options: &Options,
+    config: ExerciseConfig,
+    report: &mut Report,
+) -> Result<(), String> {
+    let mut state = ProgressState::load()
+        .map_err(|e| format!("Failed to load progress state {}: {}", STATE_FILE, e))?;
 
-    for exercise in all_exercises {
-        println!("\nEvaluating {}: {}", exercise.exercise_type, exercise.name);
-        let result = evaluate_exercise(&exercise);
+    let selection = options.selection.clone();
+    let mut all_exercises = selection.apply(config.exercises())?;
+    let context = EvaluationContext::new(options, &config);
+    // watch 总是从第一道未通过的习题开始（全部通过时重新评测全部习题），run 需要指定 --resume；
+    // 跳过的习题此前均已通过且内容未变，沿用进度文件中的结论写入报告，总分不会因此偏低
+    let mut start = 0;
+    if options.resume || mode == "watch" {
+        let current_hash = |exercise: &Exercise| context.cache.hash(exercise, &context.limits);
+        match state.first_unsolved(&all_exercises, current_hash) {
+            Some(index) => start = index,
+            None if mode == "watch" => {}
+            None => {
+                return Err("All selected exercises are already solved; nothing to resume".into())
+            }
+        }
+        if start > 0 {
+            println!("Resuming from {}", all_exercises[start].name);
+        }
+    }
+    let solved: Vec<Exercise> = all_exercises.drain(..start).collect();
+    if !selection.is_empty() {
+        report.statistics.selection = Some(selection);
+    }
+    let jobs = options.jobs;
 
-        let score = if result { exercise.score } else { 0 };
+    let (all_exercises, results): (Vec<Exercise>, Vec<Evaluation>) = match mode {
+        "watch" => {
+            let results =
+                watch::watch_exercises(Path::new(EXERCISES_DIR), jobs, &all_exercises, &context)
+                    .map_err(|e| format!("Failed to watch {}: {}", EXERCISES_DIR, e))?;
+            (all_exercises, results)
+        }
+        // 提前退出 TUI 时尚未评测的习题不写入报告和进度
+        "tui" => {
+            let results = tui::run_dashboard(jobs, &all_exercises, &context, config.partial_credit)
+                .map_err(|e| format!("Terminal dashboard failed: {}", e))?;
+            all_exercises
+                .into_iter()
+                .zip(results)
+                .filter_map(|(exercise, evaluation)| Some((exercise, evaluation?)))
+                .unzip()
+        }
+        _ => {
+            let results = evaluate_all(jobs, &all_exercises, &context);
+            (all_exercises, results)
+        }
+    };
+    if let Err(e) = context.cache.save() {
+        eprintln!("Error saving build cache: {}", e);
+    }
+    for (exercise, evaluation) in all_exercises.iter().zip(&results) {
+        let score = evaluation.score(exercise, config.partial_credit);
+        let hash = context.cache.hash(exercise, &context.limits);
+        state.record(&exercise.name, evaluation.status, score, hash);
+    }
+    if let Err(e) = state.save() {
+        eprintln!("Error saving progress state: {}", e);
+    }
+
+    let reused = solved.into_iter().map(|exercise| {
+        let mut evaluation = Evaluation::new(ExerciseStatus::Passed);
+        evaluation.cached = true;
+        (exercise, evaluation)
+    });
+    for (exercise, evaluation) in reused.chain(all_exercises.into_iter().zip(results)) {
+        let result = evaluation.passed();
+        let score = evaluation.score(&exercise, config.partial_credit);
 
         report.exercises.push(ExerciseResult {
             name: exercise.name.clone(),
+            difficulty: exercise.difficulty,
             result,
+            status: evaluation.status,
             score,
+            max_score: exercise.score,
+            test_score: evaluation.test_score,
+            tests: evaluation.tests,
+            compiler_output: evaluation.compiler_output,
+            diagnostics: evaluation.diagnostics,
+            timings: evaluation.timings,
+            tampered: evaluation.tampered,
+            duration: evaluation.duration,
+            cached: evaluation.cached,
         });
 
         if result {
             report.statistics.total_successes += 1;
+        } else if evaluation.status == ExerciseStatus::InProgress {
+            report.statistics.total_in_progress += 1;
         } else {
             report.statistics.total_failures += 1;
         }
+        if evaluation.status == ExerciseStatus::TimedOut {
+            report.statistics.total_timeouts += 1;
+        }
 
-        report.statistics.total_score += score;
+        report.statistics.total_score = round_score(report.statistics.total_score + score);
+    }
+    Ok(())
+}
 
-        if mode == "watch" && !ask_to_continue() {
-            break;
+// 筛选条件的简短描述，用于输出摘要
+fn describe_selection(selection: &Selection) -> String {
+    let mut parts = Vec::new();
+    if !selection.difficulties.is_empty() {
+        let names: Vec<&str> = selection
+            .difficulties
+            .iter()
+            .map(Difficulty::as_str)
+            .collect();
+        parts.push(format!("difficulty {}", names.join(", ")));
+    }
+    if !selection.only.is_empty() {
+        parts.push(format!("only {}", selection.only.join(", ")));
+    }
+    if let Some(from) = &selection.from {
+        parts.push(format!("from {}", from));
+    }
+    parts.join("; ")
+}
+
+// 分数保留两位小数，避免浮点累加误差出现在报告中
+fn round_score(score: f64) -> f64 {
+    (score * 100.0).round() / 100.0
+}
+
+// 从测试用例的输出中解析最后一行 "Total score: NN.NN"；
+// 没有输出时（例如每个用例单独作为一个测试）按通过的测试所占比例计分
+fn parse_test_score(tests: &[TestCase]) -> Option<f64> {
+    let printed = tests
+        .iter()
+        .flat_map(|test| test.stdout.lines())
+        .filter_map(|line| line.trim().strip_prefix("Total score:"))
+        .filter_map(|score| score.trim().parse().ok())
+        .next_back();
+    let run = tests
+        .iter()
+        .filter(|test| test.status != TestStatus::Ignored)
+        .count();
+    let passed = tests
+        .iter()
+        .filter(|test| test.status == TestStatus::Ok)
+        .count();
+    printed.or((run > 0).then(|| passed as f64 * 100.0 / run as f64))
+}
+
+// 在资源限制下运行测试二进制文件，并记录每个测试用例的结果
+fn run_test_binary(
+    test_binary: &Path,
+    working_dir: &Path,
+    settings: &TestSettings,
+    deadline: Instant,
+    evaluation: &mut Evaluation,
+    output: &mut ExerciseOutput,
+) -> io::Result<()> {
+    // 工作目录不同于当前目录，需要使用绝对路径
+    let test_binary = fs::canonicalize(test_binary)?;
+    output.debug(format!("Running {}", test_binary.display()));
+    let mut command = libtest::test_binary_command(&test_binary);
+    command.current_dir(working_dir).env("TMPDIR", working_dir);
+    settings.limits.apply(&mut command);
+
+    match run_with_deadline(&mut command, deadline)? {
+        CommandOutcome::Finished(test_run) => {
+            let tests = libtest::parse_test_events(&test_run.stdout);
+            for test in tests
+                .iter()
+                .filter(|test| test.status == TestStatus::Failed)
+            {
+                output.warn(format!("    failed: {}", test.name));
+            }
+            if !test_run.status.success() && evaluation.status == ExerciseStatus::Passed {
+                evaluation.status =
+                    sandbox::limit_violation(&test_run).unwrap_or(ExerciseStatus::Failed);
+            }
+            let mut tests = tests;
+            measure_budgets(
+                &test_binary,
+                working_dir,
+                settings,
+                deadline,
+                &mut tests,
+                evaluation,
+                output,
+            )?;
+            evaluation.tests.extend(tests);
         }
+        CommandOutcome::TimedOut => evaluation.status = ExerciseStatus::TimedOut,
     }
+    Ok(())
 }
 
-fn evaluate_exercise(exercise: &Exercise) -> bool {
-    let exercise_path = PathBuf::from(&format!("./exercises/{}", exercise.path));
-    match exercise.exercise_type.as_str() {
-        "single_file" => evaluate_single_file(&exercise_path),
-        "cargo_project" => evaluate_cargo_project(&exercise_path),
-        _ => {
-            eprintln!("Unknown exercise type: {}", exercise.exercise_type);
-            false
+// 对有时间预算且已通过的测试计时，超出预算的测试记为未通过
+fn measure_budgets(
+    test_binary: &Path,
+    working_dir: &Path,
+    settings: &TestSettings,
+    deadline: Instant,
+    tests: &mut [TestCase],
+    evaluation: &mut Evaluation,
+    output: &mut ExerciseOutput,
+) -> io::Result<()> {
+    for test in tests.iter_mut() {
+        let Some(&budget_ms) = settings.budgets_ms.get(&test.name) else {
+            continue;
+        };
+        if test.status != TestStatus::Ok {
+            continue;
+        }
+        let measurement = timing::measure_test(
+            test_binary,
+            working_dir,
+            &test.name,
+            budget_ms,
+            settings.repetitions,
+            settings.limits,
+            deadline,
+        )?;
+        let within_budget = match measurement {
+            Measurement::Timed(timing) => {
+                output.info(format!(
+                    "    timing: {} median {:.2}ms, max {:.2}ms over {} runs (budget {}ms)",
+                    test.name, timing.median_ms, timing.max_ms, timing.repetitions, budget_ms
+                ));
+                let within_budget = timing.within_budget();
+                if !within_budget {
+                    output.warn(format!("    over budget: {}", test.name));
+                }
+                evaluation.timings.push(timing);
+                within_budget
+            }
+            Measurement::Failed => {
+                output.warn(format!("    failed while timing: {}", test.name));
+                false
+            }
+            Measurement::TimedOut => {
+                evaluation.status = ExerciseStatus::TimedOut;
+                return Ok(());
+            }
+        };
+        if !within_budget {
+            test.status = TestStatus::Failed;
+            if evaluation.status == ExerciseStatus::Passed {
+                evaluation.status = ExerciseStatus::Failed;
+            }
+        }
+    }
+    Ok(())
+}
+
+// 评测全部习题，结果按配置顺序返回
+fn evaluate_all(
+    jobs: usize,
+    exercises: &[Exercise],
+    context: &EvaluationContext,
+) -> Vec<Evaluation> {
+    if jobs <= 1 {
+        evaluate_sequentially(exercises, context)
+    } else {
+        evaluate_in_parallel(jobs, exercises, context)
+    }
+}
+
+// 逐题评测
+fn evaluate_sequentially(exercises: &[Exercise], context: &EvaluationContext) -> Vec<Evaluation> {
+    let mut results = Vec::with_capacity(exercises.len());
+
+    for exercise in exercises {
+        let mut output = ExerciseOutput::default();
+        let result = evaluate_exercise(exercise, context, &mut output);
+        output.flush();
+        results.push(result);
+    }
+
+    results
+}
+
+// 使用固定数量的工作线程并发评测，结果按配置顺序返回
+fn evaluate_in_parallel(
+    jobs: usize,
+    exercises: &[Exercise],
+    context: &EvaluationContext,
+) -> Vec<Evaluation> {
+    let next_index = AtomicUsize::new(0);
+    let mut results = vec![Evaluation::new(ExerciseStatus::Failed); exercises.len()];
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.min(exercises.len()) {
+            let sender = sender.clone();
+            let next_index = &next_index;
+            scope.spawn(move || loop {
+                let index = next_index.fetch_add(1, Ordering::SeqCst);
+                let Some(exercise) = exercises.get(index) else {
+                    break;
+                };
+                let mut output = ExerciseOutput::default();
+                let result = evaluate_exercise(exercise, context, &mut output);
+                if sender.send((index, result, output)).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        // 每道题的输出在完成后整体打印，避免不同习题的输出交错
+        for (index, result, output) in receiver {
+            output.flush();
+            results[index] = result;
+        }
+    });
+
+    results
+}
+
+fn evaluate_exercise(
+    exercise: &Exercise,
+    context: &EvaluationContext,
+    output: &mut ExerciseOutput,
+) -> Evaluation {
+    let _scope = logging::ExerciseScope::enter(&exercise.name);
+    output.info(format!(
+        "\nEvaluating {}: {}",
+        exercise.exercise_type, exercise.name
+    ));
+    // 受保护的文件被修改时不评测，也不读取缓存
+    let tampered = tamper::tampered_files(exercise);
+    if !tampered.is_empty() {
+        output.error(format!(
+            "{}: TAMPERED (protected files were modified: {})",
+            exercise.name,
+            tampered.join(", ")
+        ));
+        let mut evaluation = Evaluation::new(ExerciseStatus::Tampered);
+        evaluation.tampered = tampered;
+        return evaluation;
+    }
+
+    let exercise_path = Path::new(EXERCISES_DIR).join(&exercise.path);
+    if has_not_done_marker(&exercise_path) {
+        output.info(format!(
+            "{}: in progress (remove the `// I AM NOT DONE` line when you are done)",
+            exercise.name
+        ));
+        return Evaluation::new(ExerciseStatus::InProgress);
+    }
+
+    let cache = &context.cache;
+    let hash = cache.hash(exercise, &context.limits);
+    // 首次评测 Cargo 项目时会生成 Cargo.lock，需按评测后的内容记录哈希，否则下次评测无法命中缓存
+    let creates_lock = exercise.exercise_type == ExerciseType::CargoProject
+        && !exercise_path.join("Cargo.lock").exists();
+    if let Some(mut evaluation) = hash.as_ref().and_then(|hash| cache.lookup(exercise, hash)) {
+        output.info(format!(
+            "{}: {} (unchanged, using cached result)",
+            exercise.name,
+            evaluation.status.as_str()
+        ));
+        evaluation.cached = true;
+        return evaluation;
+    }
+
+    let settings = TestSettings {
+        limits: &context.limits,
+        budgets_ms: &exercise.budgets_ms,
+        repetitions: exercise.repetitions(),
+        hidden_tests: exercise.hidden_tests_path(),
+    };
+    let start_time = Instant::now();
+    let deadline = start_time + exercise.timeout();
+    let mut evaluation = match exercise.exercise_type {
+        ExerciseType::SingleFile => {
+            evaluate_single_file(&exercise_path, &settings, deadline, output)
         }
+        ExerciseType::CargoProject => evaluate_cargo_project(
+            &exercise_path,
+            context.shared_target_dir.as_deref(),
+            &settings,
+            deadline,
+            output,
+        ),
+    };
+    evaluation.duration = start_time.elapsed().as_secs_f64();
+    log::info!(
+        "Finished with status {} in {:.2}s",
+        evaluation.status.as_str(),
+        evaluation.duration
+    );
+    let hash = if creates_lock {
+        cache.hash(exercise, &context.limits)
+    } else {
+        hash
+    };
+    if let Some(hash) = hash {
+        cache.store(exercise, hash, &evaluation);
     }
+    evaluation
+}
+
+// 习题的任一源文件中含有 "// I AM NOT DONE" 标记时，视为尚未完成
+fn has_not_done_marker(exercise_path: &Path) -> bool {
+    let mut files = Vec::new();
+    if cache::collect_files(exercise_path, &mut files).is_err() {
+        return false;
+    }
+    files
+        .iter()
+        .filter(|file| file.extension().is_some_and(|extension| extension == "rs"))
+        .filter_map(|file| fs::read_to_string(file).ok())
+        .any(|source| {
+            source.lines().any(|line| {
+                line.trim()
+                    .strip_prefix("//")
+                    .is_some_and(|comment| comment.trim() == "I AM NOT DONE")
+            })
+        })
 }
 
 // 评测单文件 Rust 习题（使用 rustc --test 并执行测试）
-fn evaluate_single_file(file_path: &PathBuf) -> bool {
+fn evaluate_single_file(
+    file_path: &Path,
+    settings: &TestSettings,
+    deadline: Instant,
+    output: &mut ExerciseOutput,
+) -> Evaluation {
     // 获取文件名（不带扩展名）
     let test_binary = file_path.with_extension("");
+    output.phase(Phase::Building);
+    output.debug(format!("Running rustc --test {}", file_path.display()));
 
     // 编译测试文件
-    let compile_output = Command::new("rustc")
-        .arg("--test") // 使用 rustc --test 进行编译
-        .arg(file_path)
-        .arg("-o")
-        .arg(&test_binary) // 指定输出文件
-        .output();
-
-    if let Ok(output) = compile_output {
-        if output.status.success() {
-            // 编译成功，运行测试二进制文件
-            let test_output = Command::new(&test_binary).output();
-
-            let test_passed = match test_output {
-                Ok(test_run) => {
-                    if test_run.status.success() {
-                        println!("\x1b[32m{}: TEST PASSED\x1b[0m", file_path.display());
-                        true
-                    } else {
-                        println!("\x1b[31m{}: TEST FAILED\x1b[0m", file_path.display());
-                        false
+    let compile_output = run_with_deadline(
+        Command::new("rustc")
+            .arg("--test") // 使用 rustc --test 进行编译
+            .arg("--error-format=json") // 诊断以 JSON 输出，便于写入报告
+            .arg(file_path)
+            .arg("-o")
+            .arg(&test_binary), // 指定输出文件
+        deadline,
+    );
+
+    match compile_output {
+        Ok(CommandOutcome::Finished(compiled)) => {
+            if compiled.status.success() {
+                // 编译成功，运行测试二进制文件
+                let mut evaluation = Evaluation::new(ExerciseStatus::Passed);
+                evaluation.add_diagnostics(diagnostics::parse_rustc_diagnostics(&compiled.stderr));
+                // 测试在空的私有临时目录中运行
+                output.phase(Phase::Testing);
+                let test_run = PrivateDir::new(None).and_then(|dir| {
+                    run_test_binary(
+                        &test_binary,
+                        dir.path(),
+                        settings,
+                        deadline,
+                        &mut evaluation,
+                        output,
+                    )
+                });
+
+                // 没有运行任何测试时不能算作通过
+                if test_run.is_ok()
+                    && evaluation.status == ExerciseStatus::Passed
+                    && evaluation.tests.is_empty()
+                {
+                    output.error(format!("{}: no tests were run", file_path.display()));
+                    evaluation.status = ExerciseStatus::Failed;
+                }
+
+                match (test_run, evaluation.status) {
+                    (Err(_), _) => {
+                        output.error(format!(
+                            "Error running test executable for {}",
+                            file_path.display()
+                        ));
+                        evaluation.status = ExerciseStatus::Failed;
                     }
+                    (Ok(()), ExerciseStatus::Passed) => output.info(
+                        style::success(&format!("{}: TEST PASSED", file_path.display()))
+                            .to_string(),
+                    ),
+                    (Ok(()), ExerciseStatus::Failed) => output.warn(
+                        style::failure(&format!("{}: TEST FAILED", file_path.display()))
+                            .to_string(),
+                    ),
+                    (Ok(()), status) => output.warn(
+                        style::failure(&format!("{}: {}", file_path.display(), status.label()))
+                            .to_string(),
+                    ),
                 }
-                Err(_) => {
-                    eprintln!("Error running test executable for {}", file_path.display());
-                    false
+                evaluation.test_score = parse_test_score(&evaluation.tests);
+
+                // 删除测试二进制文件
+                output.phase(Phase::Cleanup);
+                if let Err(e) = fs::remove_file(&test_binary) {
+                    output.warn(format!(
+                        "Failed to remove test binary {}: {}",
+                        test_binary.display(),
+                        e
+                    ));
+                } else {
+                    output.debug(format!(
+                        "Successfully removed test binary: {}",
+                        test_binary.display()
+                    ));
                 }
-            };
-
-            // 删除测试二进制文件
-            if let Err(e) = fs::remove_file(&test_binary) {
-                eprintln!(
-                    "Failed to remove test binary {}: {}",
-                    test_binary.display(),
-                    e
-                );
+
+                evaluation
             } else {
-                println!(
-                    "Successfully removed test binary: {}",
-                    test_binary.display()
+                // 编译失败
+                output.error(
+                    style::failure(&format!("{}: COMPILATION FAILED", file_path.display()))
+                        .to_string(),
                 );
+                let mut evaluation = Evaluation::new(ExerciseStatus::Failed);
+                let diagnostics = diagnostics::parse_rustc_diagnostics(&compiled.stderr);
+                evaluation.add_compile_failure(diagnostics, &compiled.stderr, output);
+                evaluation
             }
-
-            return test_passed;
-        } else {
-            // 编译失败
-            eprintln!("\x1b[31m{}: COMPILATION FAILED\x1b[0m", file_path.display());
-            return false;
         }
-    } else {
-        eprintln!("Error executing rustc --test for {}", file_path.display());
-        return false;
+        Ok(CommandOutcome::TimedOut) => {
+            // 编译超时时可能残留未写完的二进制文件
+            let _ = fs::remove_file(&test_binary);
+            output.warn(style::failure(&format!("{}: TIMED OUT", file_path.display())).to_string());
+            Evaluation::new(ExerciseStatus::TimedOut)
+        }
+        Err(_) => {
+            output.error(format!(
+                "Error executing rustc --test for {}",
+                file_path.display()
+            ));
+            Evaluation::new(ExerciseStatus::Failed)
+        }
     }
 }
 
 // 评测 Cargo 项目
-fn evaluate_cargo_project(proj_path: &PathBuf) -> bool {
-    let build_success = run_cargo_command(proj_path, "build");
-    let test_success = run_cargo_command(proj_path, "test");
-    let clippy_success = run_cargo_command(proj_path, "clippy");
+fn evaluate_cargo_project(
+    proj_path: &Path,
+    target_dir: Option<&Path>,
+    settings: &TestSettings,
+    deadline: Instant,
+    output: &mut ExerciseOutput,
+) -> Evaluation {
+    let mut evaluation = Evaluation::new(ExerciseStatus::Passed);
+    let mut gates_passed = true;
+    for (command, phase) in [
+        ("build", Phase::Building),
+        ("test", Phase::Testing),
+        ("clippy", Phase::Linting),
+    ] {
+        output.phase(phase);
+        let step = if command == "test" {
+            run_cargo_tests(
+                proj_path,
+                target_dir,
+                settings,
+                deadline,
+                &mut evaluation,
+                output,
+            )
+        } else {
+            let args = [command, "--message-format=json"];
+            run_cargo_command(proj_path, target_dir, &args, deadline, output).map(|outcome| {
+                match outcome {
+                    CommandOutcome::Finished(out) => {
+                        let diagnostics = diagnostics::parse_cargo_diagnostics(&out.stdout);
+                        if out.status.success() {
+                            evaluation.add_diagnostics(diagnostics);
+                            ExerciseStatus::Passed
+                        } else {
+                            evaluation.add_compile_failure(diagnostics, &out.stderr, output);
+                            ExerciseStatus::Failed
+                        }
+                    }
+                    CommandOutcome::TimedOut => ExerciseStatus::TimedOut,
+                }
+            })
+        };
 
-    let passed = build_success && test_success && clippy_success;
+        match step {
+            Ok(ExerciseStatus::Passed) => {}
+            Ok(ExerciseStatus::TimedOut) => {
+                // 超时后剩余步骤已无时间可用
+                evaluation.status = ExerciseStatus::TimedOut;
+                break;
+            }
+            Ok(ExerciseStatus::Failed) | Err(_) => {
+                // 保留更具体的结论，例如测试阶段超出了资源限制
+                if evaluation.status == ExerciseStatus::Passed {
+                    evaluation.status = ExerciseStatus::Failed;
+                }
+                // 编译或 clippy 未通过时不给部分分
+                if command != "test" {
+                    gates_passed = false;
+                }
+            }
+            Ok(status) => evaluation.status = status,
+        }
+    }
+    if gates_passed {
+        evaluation.test_score = parse_test_score(&evaluation.tests);
+    }
 
-    if passed {
-        println!("\x1b[32m{}: PASSED\x1b[0m", proj_path.display());
+    let banner = style::outcome(
+        evaluation.passed(),
+        &format!("{}: {}", proj_path.display(), evaluation.status.label()),
+    )
+    .to_string();
+    if evaluation.passed() {
+        output.info(banner);
     } else {
-        println!("\x1b[31m{}: FAILED\x1b[0m", proj_path.display());
+        output.warn(banner);
     }
 
-    clean_target_directory(proj_path);
+    // 共用的构建目录需要保留，供后续习题和下次评测复用
+    if target_dir.is_none() {
+        output.phase(Phase::Cleanup);
+        clean_target_directory(proj_path, output);
+    }
 
-    passed
+    evaluation
 }
 
-// 运行 Cargo 命令
-fn run_cargo_command(proj_path: &PathBuf, command: &str) -> bool {
-    let output = Command::new("cargo")
-        .arg(command)
-        .current_dir(proj_path)
-        .output();
+// 运行 Cargo 命令，target_dir 不为 None 时使用共用的构建目录
+fn run_cargo_command(
+    proj_path: &Path,
+    target_dir: Option<&Path>,
+    args: &[&str],
+    deadline: Instant,
+    output: &mut ExerciseOutput,
+) -> io::Result<CommandOutcome> {
+    output.debug(format!(
+        "Running cargo {} in {}",
+        args.join(" "),
+        proj_path.display()
+    ));
+    let mut command = Command::new("cargo");
+    command.args(args).current_dir(proj_path);
+    if let Some(target_dir) = target_dir {
+        command.env("CARGO_TARGET_DIR", target_dir);
+    }
+    run_with_deadline(&mut command, deadline)
+}
 
-    match output {
-        Ok(out) => out.status.success(),
-        Err(_) => false,
+// 编译 Cargo 项目的全部测试目标，并逐个运行测试二进制文件
+fn run_cargo_tests(
+    proj_path: &Path,
+    target_dir: Option<&Path>,
+    settings: &TestSettings,
+    deadline: Instant,
+    evaluation: &mut Evaluation,
+    output: &mut ExerciseOutput,
+) -> io::Result<ExerciseStatus> {
+    // 测试在项目的私有副本中编译和运行，相对路径与在项目根目录下运行 cargo test 时一致；
+    // 配置了隐藏测试时先用其覆盖副本中学生的测试
+    let working_dir = PrivateDir::new(Some(proj_path))?;
+    if let Some(hidden_tests) = &settings.hidden_tests {
+        working_dir.overlay(hidden_tests, Path::new(HIDDEN_TESTS_TARGET))?;
     }
+    // 副本位于临时目录中，仍使用项目自身的 target 目录以复用 build 步骤的依赖
+    let target_dir = match target_dir {
+        Some(target_dir) => target_dir.to_path_buf(),
+        None => fs::canonicalize(proj_path)?.join("target"),
+    };
+
+    // 构建产物信息和编译诊断均以 JSON 输出到 stdout
+    let args = ["test", "--no-run", "--message-format=json"];
+    let compiled = match run_cargo_command(
+        working_dir.path(),
+        Some(&target_dir),
+        &args,
+        deadline,
+        output,
+    )? {
+        CommandOutcome::Finished(compiled) => compiled,
+        CommandOutcome::TimedOut => return Ok(ExerciseStatus::TimedOut),
+    };
+    let diagnostics = diagnostics::parse_cargo_diagnostics(&compiled.stdout);
+    if !compiled.status.success() {
+        evaluation.add_compile_failure(diagnostics, &compiled.stderr, output);
+        return Ok(ExerciseStatus::Failed);
+    }
+    evaluation.add_diagnostics(diagnostics);
+
+    let mut tests_run = Evaluation::new(ExerciseStatus::Passed);
+    for test_binary in libtest::parse_test_executables(&compiled.stdout) {
+        run_test_binary(
+            &test_binary,
+            working_dir.path(),
+            settings,
+            deadline,
+            &mut tests_run,
+            output,
+        )?;
+        if tests_run.status == ExerciseStatus::TimedOut {
+            break;
+        }
+    }
+    if tests_run.status == ExerciseStatus::Passed {
+        if let Some(hidden_tests) = &settings.hidden_tests {
+            for name in missing_tests(hidden_tests, &tests_run.tests)? {
+                output.error(format!("    expected test {} did not run", name));
+                tests_run.status = ExerciseStatus::Failed;
+            }
+        }
+        if tests_run.tests.is_empty() {
+            output.error("    no tests were run".to_string());
+            tests_run.status = ExerciseStatus::Failed;
+        }
+    }
+    evaluation.tests.extend(tests_run.tests);
+    evaluation.timings.extend(tests_run.timings);
+    Ok(tests_run.status)
+}
+
+// 隐藏测试中声明、但没有出现在测试结果中的测试函数
+fn missing_tests(hidden_tests: &Path, tests: &[TestCase]) -> io::Result<Vec<String>> {
+    let mut files = Vec::new();
+    cache::collect_files(hidden_tests, &mut files)?;
+    let mut missing = Vec::new();
+    for file in files
+        .iter()
+        .filter(|file| file.extension().is_some_and(|extension| extension == "rs"))
+    {
+        for name in libtest::declared_tests(&fs::read_to_string(file)?) {
+            let ran = tests
+                .iter()
+                .any(|test| test.name == name || test.name.ends_with(&format!("::{}", name)));
+            if !ran {
+                missing.push(name);
+            }
+        }
+    }
+    Ok(missing)
 }
 
 // 清理 target 目录
-fn clean_target_directory(proj_path: &PathBuf) {
+fn clean_target_directory(proj_path: &Path, output: &mut ExerciseOutput) {
     let target_dir = proj_path.join("target");
 
     if target_dir.exists() {
         if let Err(e) = fs::remove_dir_all(&target_dir) {
-            eprintln!("Failed to clean up target directory: {}", e);
+            output.warn(format!("Failed to clean up target directory: {}", e));
         } else {
-            println!(
+            output.debug(format!(
                 "Successfully cleaned up target directory in: {}",
                 proj_path.display()
-            );
+            ));
         }
     }
 }
 
-// 用户确认是否继续
-fn ask_to_continue() -> bool {
-    let mut input = String::new();
-    println!("\nPress any key to continue, or 'q' to quit.");
-    io::stdin().read_line(&mut input).unwrap();
-    input.trim().to_lowercase() != "q"
-}
-
 // 保存评测报告
 fn save_report_to_json(file_name: &str, report: &Report) -> io::Result<()> {
     let file = File::create(file_name)?;
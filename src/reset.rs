@@ -0,0 +1,232 @@
+use crate::sandbox::copy_tree;
+use crate::{
+    cache, tamper, Exercise, ExerciseType, BACKUPS_DIR, EXERCISES_DIR, HIDDEN_TESTS_TARGET,
+};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// 习题初始版本的来源
+pub enum Starter {
+    // 仓库中保存的初始副本
+    Copy(PathBuf),
+    // 配置中指定的 git 版本
+    Revision(String),
+}
+
+impl Starter {
+    // 优先使用习题的 starter 副本，其次是配置中的 starter_revision
+    pub fn of(exercise: &Exercise, revision: Option<&str>) -> Option<Starter> {
+        exercise
+            .starter_path()
+            .map(Starter::Copy)
+            .or_else(|| revision.map(|revision| Starter::Revision(revision.to_string())))
+    }
+}
+
+impl fmt::Display for Starter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Starter::Copy(path) => write!(f, "{}", path.display()),
+            Starter::Revision(revision) => write!(f, "git revision {}", revision),
+        }
+    }
+}
+
+// 初始版本中的一个文件，path 相对习题目录（单文件习题为空路径）
+pub struct StarterFile {
+    path: PathBuf,
+    content: Vec<u8>,
+}
+
+// 读取初始版本中的全部文件
+pub fn starter_files(exercise: &Exercise, starter: &Starter) -> io::Result<Vec<StarterFile>> {
+    match starter {
+        Starter::Copy(root) => {
+            let mut files = Vec::new();
+            cache::collect_files(root, &mut files)?;
+            files
+                .into_iter()
+                .map(|file| {
+                    Ok(StarterFile {
+                        path: file.strip_prefix(root).unwrap_or(&file).to_path_buf(),
+                        content: fs::read(&file)?,
+                    })
+                })
+                .collect()
+        }
+        Starter::Revision(revision) => {
+            let root = Path::new(EXERCISES_DIR).join(&exercise.path);
+            let root_arg = root.to_string_lossy();
+            let listing = git(&[
+                "ls-tree",
+                "-r",
+                "-z",
+                "--name-only",
+                revision,
+                "--",
+                &root_arg,
+            ])?;
+            let mut files = Vec::new();
+            for name in listing
+                .split(|&byte| byte == 0)
+                .filter(|name| !name.is_empty())
+            {
+                let file = PathBuf::from(String::from_utf8_lossy(name).into_owned());
+                let spec = format!("{}:./{}", revision, file.display());
+                files.push(StarterFile {
+                    path: relative_to(&file, &root),
+                    content: git(&["show", &spec])?,
+                });
+            }
+            if files.is_empty() {
+                return Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("{} does not exist in {}", root.display(), revision),
+                ));
+            }
+            Ok(files)
+        }
+    }
+}
+
+// ls-tree 输出的路径不带 "./" 前缀，与习题目录比较前需统一
+fn relative_to(file: &Path, root: &Path) -> PathBuf {
+    let root = root.strip_prefix(".").unwrap_or(root);
+    file.strip_prefix(root).unwrap_or(file).to_path_buf()
+}
+
+fn git(args: &[&str]) -> io::Result<Vec<u8>> {
+    let output = Command::new("git").args(args).output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(output.stdout)
+}
+
+// Cargo 项目中由评测器持有的测试文件：受保护的文件，以及会被隐藏测试覆盖的文件
+fn test_files(exercise: &Exercise) -> BTreeSet<PathBuf> {
+    let mut files: BTreeSet<PathBuf> = exercise.protected.keys().map(PathBuf::from).collect();
+    files.insert(PathBuf::from(HIDDEN_TESTS_TARGET));
+    if let Some(hidden_tests) = exercise.hidden_tests_path().filter(|path| path.is_dir()) {
+        let mut hidden_files = Vec::new();
+        if cache::collect_files(&hidden_tests, &mut hidden_files).is_ok() {
+            files.extend(
+                hidden_files
+                    .iter()
+                    .filter_map(|file| file.strip_prefix(&hidden_tests).ok())
+                    .map(Path::to_path_buf),
+            );
+        }
+    }
+    files
+}
+
+// 单文件习题：用初始版本替换测试模块之前的部分，保留当前的测试模块
+fn splice_starter(starter: &str, current: &str) -> String {
+    let Some(tests) = tamper::test_module(current) else {
+        return starter.to_string();
+    };
+    let solution_len = starter.len() - tamper::test_module(starter).map_or(0, str::len);
+    let solution = &starter[..solution_len];
+    // 测试模块需要从单独的一行开始，否则之后无法识别
+    let separator = if solution.is_empty() || solution.ends_with('\n') {
+        ""
+    } else {
+        "\n"
+    };
+    format!("{}{}{}", solution, separator, tests)
+}
+
+// 将学生当前的作答复制到 .cargotest/backups/<习题名>-<时间戳>，返回备份位置
+pub fn backup(exercise: &Exercise) -> io::Result<PathBuf> {
+    let source = Path::new(EXERCISES_DIR).join(&exercise.path);
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let destination = Path::new(BACKUPS_DIR).join(format!("{}-{}", exercise.name, timestamp));
+    match exercise.exercise_type {
+        ExerciseType::SingleFile => {
+            fs::create_dir_all(&destination)?;
+            fs::copy(&source, destination.join(&exercise.name))?;
+        }
+        ExerciseType::CargoProject => copy_tree(&source, &destination)?,
+    }
+    Ok(destination)
+}
+
+// 用初始版本覆盖学生可修改的文件，测试文件保持不变，返回恢复的文件
+pub fn restore(exercise: &Exercise, files: &[StarterFile]) -> io::Result<Vec<PathBuf>> {
+    let exercise_path = Path::new(EXERCISES_DIR).join(&exercise.path);
+    match exercise.exercise_type {
+        ExerciseType::SingleFile => {
+            let starter = files
+                .first()
+                .map(|file| String::from_utf8_lossy(&file.content).into_owned())
+                .unwrap_or_default();
+            let current = fs::read_to_string(&exercise_path).unwrap_or_default();
+            fs::write(&exercise_path, splice_starter(&starter, &current))?;
+            Ok(vec![exercise_path])
+        }
+        ExerciseType::CargoProject => {
+            let tests = test_files(exercise);
+            let mut restored = Vec::new();
+            for file in files.iter().filter(|file| !tests.contains(&file.path)) {
+                let target = exercise_path.join(&file.path);
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(&target, &file.content)?;
+                restored.push(target);
+            }
+            Ok(restored)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TESTS: &str = "#[cfg(test)]\nmod tests {\n    #[test]\n    fn test_solve() {}\n}\n";
+
+    #[test]
+    fn splice_starter_keeps_current_test_module() {
+        let starter = format!(
+            "pub fn solve() {{\n    todo!()\n}}\n\n{}",
+            "#[cfg(test)]\nmod tests {}\n"
+        );
+        let current = format!("pub fn solve() {{\n    42\n}}\n\n{}", TESTS);
+        assert_eq!(
+            splice_starter(&starter, &current),
+            format!("pub fn solve() {{\n    todo!()\n}}\n\n{}", TESTS)
+        );
+    }
+
+    #[test]
+    fn splice_starter_without_starter_test_module() {
+        let current = format!("pub fn solve() {{}}\n{}", TESTS);
+        assert_eq!(
+            splice_starter("pub fn solve() {\n    todo!()\n}\n", &current),
+            format!("pub fn solve() {{\n    todo!()\n}}\n{}", TESTS)
+        );
+        // 初始版本末尾没有换行时，测试模块仍从单独的一行开始
+        let spliced = splice_starter("pub fn solve() {}", &current);
+        assert_eq!(spliced, format!("pub fn solve() {{}}\n{}", TESTS));
+        assert_eq!(tamper::test_module(&spliced), Some(TESTS));
+    }
+
+    #[test]
+    fn splice_starter_without_current_test_module() {
+        let starter = format!("pub fn solve() {{}}\n{}", TESTS);
+        assert_eq!(splice_starter(&starter, "pub fn solve() {}\n"), starter);
+        assert_eq!(splice_starter(&starter, ""), starter);
+    }
+}
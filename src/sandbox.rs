@@ -0,0 +1,164 @@
+use crate::cache;
+use crate::ExerciseStatus;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, DirBuilder};
+use std::io;
+use std::os::unix::fs::DirBuilderExt;
+use std::os::unix::process::{CommandExt, ExitStatusExt};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// 运行测试二进制文件时的资源限制，可在配置文件顶层的 limits 中修改
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(default)]
+pub struct ResourceLimits {
+    // 地址空间上限（MiB）
+    pub memory_mb: u64,
+    // CPU 时间上限（秒），所有线程累计
+    pub cpu_secs: u64,
+    // 当前用户可拥有的进程（含线程）数上限，对 root 用户无效。RLIMIT_NPROC 统计的是
+    // 该用户名下的全部进程和线程，而不只是测试派生的，因此默认不设置
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub processes: Option<u64>,
+    // 单个文件大小上限（MiB）
+    pub file_size_mb: u64,
+}
+
+impl Default for ResourceLimits {
+    fn default() -> Self {
+        ResourceLimits {
+            memory_mb: 2048,
+            cpu_secs: 60,
+            processes: None,
+            file_size_mb: 64,
+        }
+    }
+}
+
+impl ResourceLimits {
+    // 在子进程 exec 之前设置资源限制，子进程派生的进程会继承这些限制
+    pub fn apply(&self, command: &mut Command) {
+        const MIB: u64 = 1024 * 1024;
+        let limits = [
+            Some((libc::RLIMIT_AS, self.memory_mb * MIB, self.memory_mb * MIB)),
+            // 超过软限制时收到 SIGXCPU，再超过 1 秒被 SIGKILL 结束
+            Some((libc::RLIMIT_CPU, self.cpu_secs, self.cpu_secs + 1)),
+            self.processes
+                .map(|processes| (libc::RLIMIT_NPROC, processes, processes)),
+            Some((
+                libc::RLIMIT_FSIZE,
+                self.file_size_mb * MIB,
+                self.file_size_mb * MIB,
+            )),
+        ];
+        // 只调用 async-signal-safe 的 setrlimit，不分配内存
+        unsafe {
+            command.pre_exec(move || {
+                for (resource, soft, hard) in limits.into_iter().flatten() {
+                    let limit = libc::rlimit {
+                        rlim_cur: soft as libc::rlim_t,
+                        rlim_max: hard as libc::rlim_t,
+                    };
+                    if libc::setrlimit(resource, &limit) != 0 {
+                        return Err(io::Error::last_os_error());
+                    }
+                }
+                Ok(())
+            });
+        }
+    }
+}
+
+// 根据退出信号和错误输出判断测试进程是否因超出资源限制而结束
+pub fn limit_violation(output: &Output) -> Option<ExerciseStatus> {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    match output.status.signal() {
+        Some(libc::SIGXCPU) => return Some(ExerciseStatus::CpuLimitExceeded),
+        Some(libc::SIGXFSZ) => return Some(ExerciseStatus::FileSizeLimitExceeded),
+        // RLIMIT_CPU 的硬限制以 SIGKILL 结束进程；grader 自己只在超时时发送 SIGKILL
+        Some(libc::SIGKILL) => return Some(ExerciseStatus::CpuLimitExceeded),
+        _ => {}
+    }
+    // Rust 的全局分配器分配失败时输出该信息并中止进程
+    if stderr.contains("memory allocation of") && stderr.contains("failed") {
+        return Some(ExerciseStatus::MemoryLimitExceeded);
+    }
+    // 进程数达到上限时，创建线程或子进程会返回 EAGAIN
+    if stderr.contains("failed to spawn thread") || stderr.contains("(os error 11)") {
+        return Some(ExerciseStatus::ProcessLimitExceeded);
+    }
+    None
+}
+
+// 测试运行使用的私有临时工作目录，离开作用域时删除
+pub struct PrivateDir {
+    path: PathBuf,
+}
+
+impl PrivateDir {
+    // 创建临时目录，并复制 source 中除 target 目录和隐藏文件之外的全部文件，
+    // 使测试仍能按相对路径读取项目中的数据文件
+    pub fn new(source: Option<&Path>) -> io::Result<Self> {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+        // 只有当前用户可以访问；目录名可以预测，不能复用已存在的目录
+        let mut builder = DirBuilder::new();
+        builder.mode(0o700);
+        let path = loop {
+            let path = std::env::temp_dir().join(format!(
+                "cargotest-{}-{}",
+                std::process::id(),
+                NEXT_ID.fetch_add(1, Ordering::SeqCst)
+            ));
+            match builder.create(&path) {
+                Ok(()) => break path,
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        };
+        let dir = PrivateDir { path };
+
+        if let Some(source) = source {
+            copy_tree(source, &dir.path)?;
+        }
+        Ok(dir)
+    }
+
+    // 用 grader 持有的测试覆盖目录中的文件：目录按相对路径覆盖，单个文件替换 file_target
+    pub fn overlay(&self, source: &Path, file_target: &Path) -> io::Result<()> {
+        if source.is_dir() {
+            copy_tree(source, &self.path)
+        } else {
+            let destination = self.path.join(file_target);
+            if let Some(parent) = destination.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(source, destination).map(|_| ())
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for PrivateDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+// 将 source 下的文件按相对路径复制到 destination，跳过 target 目录和隐藏文件
+pub fn copy_tree(source: &Path, destination: &Path) -> io::Result<()> {
+    let mut files = Vec::new();
+    cache::collect_files(source, &mut files)?;
+    for file in files {
+        let relative = file.strip_prefix(source).unwrap_or(&file);
+        let target = destination.join(relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(&file, &target)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,248 @@
+use crate::{tamper, Difficulty, ExerciseType, EXERCISES_DIR};
+use serde_json::{json, Map, Value};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// Cargo 习题中由测试文件评分、受保护的文件
+const CARGO_TESTS_FILE: &str = "src/tests.rs";
+
+// 声明测试文件如何编译的清单，同样受保护
+const CARGO_MANIFEST: &str = "Cargo.toml";
+
+// 新习题的名称、位置和分值
+pub struct NewExercise {
+    pub name: String,
+    pub difficulty: Difficulty,
+    pub exercise_type: ExerciseType,
+    pub score: i32,
+}
+
+impl NewExercise {
+    // 单文件习题的名称即文件名，省略 .rs 时自动补上
+    pub fn new(
+        name: &str,
+        difficulty: Difficulty,
+        exercise_type: ExerciseType,
+        score: i32,
+    ) -> Result<Self, String> {
+        let name = match exercise_type {
+            ExerciseType::SingleFile if !name.ends_with(".rs") => format!("{}.rs", name),
+            _ => name.to_string(),
+        };
+        let stem = name.strip_suffix(".rs").unwrap_or(&name);
+        let valid = stem.starts_with(|c: char| c.is_ascii_alphabetic())
+            && stem
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        if !valid || (exercise_type == ExerciseType::CargoProject && name.ends_with(".rs")) {
+            return Err(format!(
+                "Invalid exercise name: {} (expected letters, digits, '_' or '-', starting with a letter)",
+                name
+            ));
+        }
+        Ok(NewExercise {
+            name,
+            difficulty,
+            exercise_type,
+            score,
+        })
+    }
+
+    // 相对 exercises 目录的路径，与配置中的 path 一致
+    pub fn path(&self) -> String {
+        format!("{}/{}", self.difficulty.as_str(), self.name)
+    }
+
+    fn full_path(&self) -> PathBuf {
+        Path::new(EXERCISES_DIR).join(self.path())
+    }
+
+    // 生成习题骨架，目标已存在时不覆盖
+    pub fn create_files(&self) -> io::Result<()> {
+        let path = self.full_path();
+        if path.exists() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} already exists", path.display()),
+            ));
+        }
+        match self.exercise_type {
+            ExerciseType::SingleFile => {
+                fs::create_dir_all(path.parent().unwrap_or(Path::new(EXERCISES_DIR)))?;
+                fs::write(&path, single_file_template(&self.name))
+            }
+            ExerciseType::CargoProject => {
+                fs::create_dir_all(path.join("src"))?;
+                fs::write(path.join(CARGO_MANIFEST), cargo_manifest(&self.name))?;
+                fs::write(path.join("src/main.rs"), CARGO_MAIN)?;
+                fs::write(path.join("src/solution.rs"), SOLUTION_TEMPLATE)?;
+                fs::write(path.join(CARGO_TESTS_FILE), cargo_tests_template())
+            }
+        }
+    }
+
+    // 配置中已有同名习题时返回错误
+    pub fn check_available(&self, config: &Value) -> Result<(), String> {
+        let taken = Difficulty::ALL.iter().any(|difficulty| {
+            config[difficulty.as_str()]
+                .as_array()
+                .is_some_and(|exercises| {
+                    exercises
+                        .iter()
+                        .any(|exercise| exercise["name"].as_str() == Some(self.name.as_str()))
+                })
+        });
+        if taken {
+            return Err(format!("An exercise named {} already exists", self.name));
+        }
+        Ok(())
+    }
+
+    // 在配置中对应难度的分组末尾登记该习题，并记录测试文件和 Cargo.toml 的哈希
+    pub fn register(&self, config: &mut Value) -> Result<(), String> {
+        let keys: &[&str] = match self.exercise_type {
+            ExerciseType::SingleFile => &[tamper::TEST_MODULE],
+            ExerciseType::CargoProject => &[CARGO_TESTS_FILE, CARGO_MANIFEST],
+        };
+        let mut protected = Map::new();
+        for key in keys {
+            let hash = tamper::protected_hash(&self.full_path(), key)
+                .map_err(|e| format!("Failed to hash {}: {}", key, e))?;
+            protected.insert(key.to_string(), Value::String(hash));
+        }
+
+        let entry = json!({
+            "name": self.name,
+            "path": self.path(),
+            "type": self.exercise_type.as_str(),
+            "score": self.score,
+            "protected": protected,
+        });
+        let group = config
+            .as_object_mut()
+            .ok_or("The config file is not a JSON object")?
+            .entry(self.difficulty.as_str())
+            .or_insert_with(|| Value::Array(Vec::new()));
+        group
+            .as_array_mut()
+            .ok_or_else(|| format!("\"{}\" is not an array", self.difficulty.as_str()))?
+            .push(entry);
+        Ok(())
+    }
+}
+
+// 新习题的默认分值：与同一难度中最后一道习题相同，该难度还没有习题时为 1
+pub fn default_score(config: &Value, difficulty: Difficulty) -> i32 {
+    config[difficulty.as_str()]
+        .as_array()
+        .and_then(|exercises| exercises.last())
+        .and_then(|exercise| exercise["score"].as_i64())
+        .and_then(|score| i32::try_from(score).ok())
+        .unwrap_or(1)
+}
+
+fn cargo_manifest(name: &str) -> String {
+    format!(
+        "[package]
+name = \"{}\"
+version = \"0.1.0\"
+edition = \"2021\"
+# 不运行构建脚本，学生添加的 build.rs 无法在编译前改写测试文件
+build = false
+
+[dependencies]
+
+[[test]]
+name = \"tests\"
+path = \"{}\"
+",
+        name, CARGO_TESTS_FILE
+    )
+}
+
+const CARGO_MAIN: &str = "// I AM NOT DONE
+
+mod solution;
+
+fn main() {
+    println!(\"{}\", solution::solve(\"\"));
+}
+";
+
+const SOLUTION_TEMPLATE: &str = "// solution.rs
+pub fn solve(input: &str) -> String {
+    todo!(\"solve {}\", input)
+}
+";
+
+// 按用例平均计分并输出 "Total score" 的测试，与现有习题的评分方式一致
+const SCORING_TESTS: &str = "    // 定义测试用例和预期结果
+    const TEST_CASES: &[(&str, &str)] = &[
+        // (\"input\", \"expected\"),
+    ];
+
+    // 定义一个测试函数来验证每个测试用例
+    // 耗时由评测器按 exercise_config.json 中的 budgets_ms 检查
+    #[test]
+    fn test_solve() {
+        assert!(!TEST_CASES.is_empty(), \"no test cases defined\");
+        // 按整数统计通过的用例数，最后再换算分数，避免浮点累加达不到 100
+        let passed = TEST_CASES
+            .iter()
+            .filter(|(input, expected)| solve(input) == *expected)
+            .count();
+        let total_score = passed as f64 * 100.0 / TEST_CASES.len() as f64;
+        println!(\"Total score: {:.2}\", total_score);
+        assert_eq!(TEST_CASES.len(), passed);
+    }
+}
+";
+
+fn cargo_tests_template() -> String {
+    format!(
+        "// src/tests.rs
+mod solution;
+
+#[cfg(test)]
+mod tests {{
+    use super::solution::solve;
+
+{}",
+        SCORING_TESTS
+    )
+}
+
+fn single_file_template(name: &str) -> String {
+    format!(
+        "/*
+    {}
+    Describe the problem here
+*/
+// I AM NOT DONE
+
+pub fn solve(input: &str) -> String {{
+    todo!(\"solve {{}}\", input)
+}}
+
+#[cfg(test)]
+mod tests {{
+    use super::solve;
+
+{}",
+        name.trim_end_matches(".rs"),
+        SCORING_TESTS
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cargo_manifest_disables_build_script() {
+        assert!(crate::config::disables_build_script(&cargo_manifest(
+            "solution6"
+        )));
+    }
+}
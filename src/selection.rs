@@ -0,0 +1,127 @@
+use crate::{Difficulty, Exercise};
+use serde::{Deserialize, Serialize};
+
+// 命令行指定的习题范围，为空时评测全部习题
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Selection {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub difficulties: Vec<Difficulty>,
+    // 习题名或通配符模式，例如 algorithm7.rs、solutiont*
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub only: Vec<String>,
+    // 从该习题开始评测，跳过配置中排在它之前的习题
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+}
+
+impl Selection {
+    pub fn is_empty(&self) -> bool {
+        self.difficulties.is_empty() && self.only.is_empty() && self.from.is_none()
+    }
+
+    fn matches(&self, exercise: &Exercise) -> bool {
+        (self.difficulties.is_empty() || self.difficulties.contains(&exercise.difficulty))
+            && (self.only.is_empty()
+                || self
+                    .only
+                    .iter()
+                    .any(|pattern| glob_match(pattern, &exercise.name)))
+    }
+
+    // 按配置顺序筛选出需要评测的习题
+    pub fn apply(&self, exercises: Vec<Exercise>) -> Result<Vec<Exercise>, String> {
+        let start = match &self.from {
+            Some(name) => exercises
+                .iter()
+                .position(|exercise| &exercise.name == name)
+                .ok_or_else(|| format!("Unknown exercise for --from: {}", name))?,
+            None => 0,
+        };
+
+        for pattern in &self.only {
+            if !exercises
+                .iter()
+                .any(|exercise| glob_match(pattern, &exercise.name))
+            {
+                return Err(format!("No exercise matches --only {}", pattern));
+            }
+        }
+
+        let selected: Vec<Exercise> = exercises
+            .into_iter()
+            .skip(start)
+            .filter(|exercise| self.matches(exercise))
+            .collect();
+        if selected.is_empty() {
+            return Err("No exercises selected".to_string());
+        }
+        Ok(selected)
+    }
+}
+
+// 通配符匹配，支持 `*`（任意个字符）和 `?`（单个字符）
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // 最近一个 `*` 在模式中的位置，以及它当时匹配到的名称位置
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    n = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::glob_match;
+
+    #[test]
+    fn glob_match_literal_and_question_mark() {
+        assert!(glob_match("algorithm7.rs", "algorithm7.rs"));
+        assert!(!glob_match("algorithm7.rs", "algorithm17.rs"));
+        assert!(glob_match("algorithm?.rs", "algorithm7.rs"));
+        assert!(!glob_match("algorithm?.rs", "algorithm17.rs"));
+        assert!(!glob_match("solution", "solution1"));
+        assert!(!glob_match("solution1", "solution"));
+    }
+
+    #[test]
+    fn glob_match_star() {
+        assert!(glob_match("*", ""));
+        assert!(glob_match("*", "solutiont1"));
+        assert!(glob_match("solutiont*", "solutiont"));
+        assert!(glob_match("solutiont*", "solutiont5"));
+        assert!(!glob_match("solutiont*", "solution5"));
+        assert!(glob_match("**.rs", "algorithm1.rs"));
+        assert!(!glob_match("*.rs", "solution1"));
+    }
+
+    // `*` 首次匹配失败后需要回溯，多吞一个字符再试
+    #[test]
+    fn glob_match_star_backtracks() {
+        assert!(glob_match("*1.rs", "algorithm11.rs"));
+        assert!(glob_match("a*b*c", "aXbYbZc"));
+        assert!(glob_match("*ab", "aab"));
+        assert!(!glob_match("a*b*c", "aXbYbZ"));
+        assert!(!glob_match("*x*", "algorithm"));
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{Exercise, ExerciseStatus};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// 各习题最近一次评测结论的记录文件，跨多次评测保留
+pub const STATE_FILE: &str = ".cargotest/state.json";
+
+// 单道习题最近一次的评测结论
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExerciseProgress {
+    pub status: ExerciseStatus,
+    pub score: f64,
+    // 评测完成的时间（Unix 时间戳，秒）
+    pub timestamp: u64,
+    // 评测时习题内容的哈希，工具链不可用时为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct ProgressState {
+    #[serde(default)]
+    pub exercises: BTreeMap<String, ExerciseProgress>,
+}
+
+impl ProgressState {
+    // 读取进度文件，尚未评测过时返回空进度
+    pub fn load() -> io::Result<Self> {
+        match File::open(STATE_FILE) {
+            Ok(file) => Ok(serde_json::from_reader(file)?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(ProgressState::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let path = Path::new(STATE_FILE);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = File::create(path)?;
+        serde_json::to_writer_pretty(file, self)?;
+        Ok(())
+    }
+
+    pub fn record(&mut self, name: &str, status: ExerciseStatus, score: f64, hash: Option<String>) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        self.exercises.insert(
+            name.to_string(),
+            ExerciseProgress {
+                status,
+                score,
+                timestamp,
+                hash,
+            },
+        );
+    }
+
+    // 最近一次评测通过，并且评测时的内容哈希与当前一致
+    pub fn is_solved(&self, name: &str, hash: Option<&str>) -> bool {
+        self.exercises.get(name).is_some_and(|progress| {
+            progress.status == ExerciseStatus::Passed
+                && hash.is_some()
+                && progress.hash.as_deref() == hash
+        })
+    }
+
+    // 按配置顺序找到第一道尚未通过（或通过后又被修改）的习题的位置，hash 计算习题当前内容的哈希
+    pub fn first_unsolved(
+        &self,
+        exercises: &[Exercise],
+        hash: impl Fn(&Exercise) -> Option<String>,
+    ) -> Option<usize> {
+        exercises
+            .iter()
+            .position(|exercise| !self.is_solved(&exercise.name, hash(exercise).as_deref()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_solved_requires_matching_hash() {
+        let mut state = ProgressState::default();
+        state.record("a", ExerciseStatus::Passed, 1.0, Some("1111".to_string()));
+        state.record("b", ExerciseStatus::Failed, 0.0, Some("2222".to_string()));
+        state.record("c", ExerciseStatus::Passed, 1.0, None);
+        assert!(state.is_solved("a", Some("1111")));
+        assert!(!state.is_solved("a", Some("3333")));
+        assert!(!state.is_solved("a", None));
+        assert!(!state.is_solved("b", Some("2222")));
+        assert!(!state.is_solved("c", None));
+        assert!(!state.is_solved("d", Some("1111")));
+    }
+}
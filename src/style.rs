@@ -0,0 +1,57 @@
+use colored::{ColoredString, Colorize};
+use std::io::{self, IsTerminal};
+
+// --color 的取值
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorChoice {
+    // 输出到终端且未设置 NO_COLOR 时使用颜色
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "auto" => Some(ColorChoice::Auto),
+            "always" => Some(ColorChoice::Always),
+            "never" => Some(ColorChoice::Never),
+            _ => None,
+        }
+    }
+}
+
+// 决定本次运行是否输出颜色，之后所有经由 colored 的样式都遵循该设置
+pub fn init(choice: ColorChoice) {
+    let enabled = match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        // 按 https://no-color.org 的约定，NO_COLOR 为空字符串时视为未设置
+        ColorChoice::Auto => {
+            std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+                && io::stdout().is_terminal()
+                && io::stderr().is_terminal()
+        }
+    };
+    colored::control::set_override(enabled);
+}
+
+// 通过的结论
+pub fn success(text: &str) -> ColoredString {
+    text.green()
+}
+
+// 未通过的结论和错误
+pub fn failure(text: &str) -> ColoredString {
+    text.red()
+}
+
+// 按是否通过选择样式
+pub fn outcome(passed: bool, text: &str) -> ColoredString {
+    if passed {
+        success(text)
+    } else {
+        failure(text)
+    }
+}
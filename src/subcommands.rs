@@ -0,0 +1,407 @@
+use crate::cli::NewOptions;
+use crate::libtest::TestStatus;
+use crate::reset::{self, Starter};
+use crate::scaffold::{self, NewExercise};
+use crate::state::ProgressState;
+use crate::tamper;
+use crate::{
+    evaluate_exercise, load_exercise_config, Difficulty, EvaluationContext, Exercise,
+    ExerciseConfig, ExerciseOutput, ExerciseStatus, Report, CONFIG_FILE, EXERCISES_DIR,
+    EXIT_CONFIG, EXIT_ERROR, EXIT_FAILURE, REPORT_FILE,
+};
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::Path;
+
+// list 中进度条的宽度（字符数）
+const PROGRESS_BAR_WIDTH: usize = 20;
+
+fn load_config() -> Result<ExerciseConfig, i32> {
+    load_exercise_config(CONFIG_FILE).map_err(|e| {
+        eprintln!("Failed to load config file: {}", e);
+        EXIT_CONFIG
+    })
+}
+
+fn load_exercises() -> Result<Vec<Exercise>, i32> {
+    Ok(load_config()?.exercises())
+}
+
+fn find_exercise(config: &ExerciseConfig, name: &str) -> Result<Exercise, i32> {
+    config
+        .exercises()
+        .into_iter()
+        .find(|exercise| exercise.name == name)
+        .ok_or_else(|| {
+            eprintln!("Unknown exercise: {}", name);
+            EXIT_ERROR
+        })
+}
+
+fn load_report(path: &Path) -> io::Result<Report> {
+    let file = File::open(path)?;
+    let mut report: serde_json::Value = serde_json::from_reader(file)?;
+
+    // 旧版报告只有 result 字段，据此补全 status
+    if let Some(exercises) = report["exercises"].as_array_mut() {
+        for exercise in exercises {
+            if exercise.get("status").is_none() {
+                let status = if exercise["result"].as_bool() == Some(true) {
+                    "passed"
+                } else {
+                    "failed"
+                };
+                exercise["status"] = status.into();
+            }
+        }
+    }
+    Ok(serde_json::from_value(report)?)
+}
+
+// list：列出全部习题及最近一次评测的结果，并按难度显示进度
+pub fn list_exercises() -> i32 {
+    let exercises = match load_exercises() {
+        Ok(exercises) => exercises,
+        Err(code) => return code,
+    };
+    // 优先使用进度文件，其次是上次保存的报告；都没有时状态显示为 "-"
+    let state = ProgressState::load().unwrap_or_default();
+    let report = load_report(Path::new(REPORT_FILE)).ok();
+    let status_of = |exercise: &Exercise| {
+        if let Some(progress) = state.exercises.get(&exercise.name) {
+            return progress.status.as_str();
+        }
+        report
+            .as_ref()
+            .and_then(|report| {
+                report
+                    .exercises
+                    .iter()
+                    .find(|result| result.name == exercise.name)
+            })
+            .map_or("-", |result| result.status.as_str())
+    };
+
+    println!(
+        "{:<16} {:<10} {:<14} {:>5}  Status",
+        "Name", "Difficulty", "Type", "Score"
+    );
+    for exercise in &exercises {
+        println!(
+            "{:<16} {:<10} {:<14} {:>5}  {}",
+            exercise.name,
+            exercise.difficulty.as_str(),
+            exercise.exercise_type,
+            exercise.score,
+            status_of(exercise)
+        );
+    }
+
+    println!("\nProgress:");
+    for difficulty in Difficulty::ALL {
+        let group: Vec<&Exercise> = exercises
+            .iter()
+            .filter(|exercise| exercise.difficulty == difficulty)
+            .collect();
+        if group.is_empty() {
+            continue;
+        }
+        let solved = group
+            .iter()
+            .filter(|exercise| status_of(exercise) == ExerciseStatus::Passed.as_str())
+            .count();
+        println!(
+            "  {:<8} {} {:>3}/{}",
+            difficulty.as_str(),
+            progress_bar(solved, group.len()),
+            solved,
+            group.len()
+        );
+    }
+    0
+}
+
+// 形如 [########------------] 的进度条
+fn progress_bar(done: usize, total: usize) -> String {
+    let filled = done * PROGRESS_BAR_WIDTH / total.max(1);
+    format!(
+        "[{}{}]",
+        "#".repeat(filled),
+        "-".repeat(PROGRESS_BAR_WIDTH - filled)
+    )
+}
+
+// verify：只评测一道习题，不修改已保存的报告
+pub fn verify_exercise(name: &str) -> i32 {
+    let (config, exercise) = match load_config()
+        .and_then(|config| find_exercise(&config, name).map(|exercise| (config, exercise)))
+    {
+        Ok(found) => found,
+        Err(code) => return code,
+    };
+
+    let mut output = ExerciseOutput::default();
+    let context = EvaluationContext::standalone(&config);
+    let evaluation = evaluate_exercise(&exercise, &context, &mut output);
+    output.flush();
+
+    println!(
+        "\n{}: {} ({:.2}s)",
+        exercise.name,
+        evaluation.status.as_str(),
+        evaluation.duration
+    );
+    if evaluation.passed() {
+        0
+    } else {
+        EXIT_FAILURE
+    }
+}
+
+// hint：优先输出配置中的提示，否则输出习题源文件开头的题目描述
+pub fn show_hint(name: &str) -> i32 {
+    let exercise = match load_config().and_then(|config| find_exercise(&config, name)) {
+        Ok(exercise) => exercise,
+        Err(code) => return code,
+    };
+
+    let hint = exercise.hint.clone().or_else(|| {
+        let path = Path::new(EXERCISES_DIR).join(&exercise.path);
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|source| leading_block_comment(&source))
+    });
+
+    match hint {
+        Some(hint) => println!("{}", hint.trim()),
+        None => println!(
+            "No hint available for {}. See the problem statements in {}/{}/.",
+            exercise.name,
+            EXERCISES_DIR,
+            exercise.difficulty.as_str()
+        ),
+    }
+    0
+}
+
+// 提取源文件开头 /* ... */ 注释中的内容
+fn leading_block_comment(source: &str) -> Option<String> {
+    let body = source.trim_start().strip_prefix("/*")?;
+    let end = body.find("*/")?;
+    let lines: Vec<&str> = body[..end].lines().map(str::trim).collect();
+    Some(lines.join("\n"))
+}
+
+// report：格式化输出已保存的评测报告
+pub fn print_report(path: &Path) -> i32 {
+    let report = match load_report(path) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("Failed to load report {}: {}", path.display(), e);
+            return EXIT_ERROR;
+        }
+    };
+
+    for exercise in &report.exercises {
+        // 旧版报告没有记录满分
+        let score = if exercise.max_score > 0 {
+            format!("{} / {}", exercise.score, exercise.max_score)
+        } else {
+            exercise.score.to_string()
+        };
+        println!(
+            "{:<10} {:<16} {:>10} {:>8.2}s",
+            exercise.status.as_str(),
+            exercise.name,
+            score,
+            exercise.duration
+        );
+        for test in &exercise.tests {
+            if test.status == TestStatus::Failed {
+                println!("           failed: {}", test.name);
+            }
+        }
+    }
+
+    let statistics = &report.statistics;
+    println!("\nSummary:");
+    println!("Total exercises: {}", statistics.total_exercises);
+    println!("Total successes: {}", statistics.total_successes);
+    println!("Total failures: {}", statistics.total_failures);
+    println!("Total timeouts: {}", statistics.total_timeouts);
+    println!("Total in progress: {}", statistics.total_in_progress);
+    println!("Total score: {}", statistics.total_score);
+    println!("Total time: {}s", statistics.total_time);
+    0
+}
+
+// 以 JSON 值读取配置文件，写回时保留未知字段及键的顺序
+fn read_config_value() -> Result<serde_json::Value, i32> {
+    fs::read_to_string(CONFIG_FILE)
+        .map_err(|e| e.to_string())
+        .and_then(|text| serde_json::from_str(&text).map_err(|e| e.to_string()))
+        .map_err(|e| {
+            eprintln!("Failed to load config file: {}", e);
+            EXIT_CONFIG
+        })
+}
+
+fn write_config_value(config: &serde_json::Value) -> Result<(), i32> {
+    let text = serde_json::to_string_pretty(config).map_err(|e| {
+        eprintln!("Failed to serialize config: {}", e);
+        EXIT_ERROR
+    })?;
+    fs::write(CONFIG_FILE, text + "\n").map_err(|e| {
+        eprintln!("Failed to write {}: {}", CONFIG_FILE, e);
+        EXIT_ERROR
+    })
+}
+
+// seal：重新计算受保护文件的哈希并写回配置文件，保留其余内容及键的顺序
+pub fn seal_protected_files() -> i32 {
+    let mut config = match read_config_value() {
+        Ok(config) => config,
+        Err(code) => return code,
+    };
+
+    let sealed = match tamper::seal(&mut config) {
+        Ok(sealed) => sealed,
+        Err(errors) => {
+            eprintln!("Failed to seal protected files:");
+            for error in errors {
+                eprintln!("  {}", error);
+            }
+            return EXIT_ERROR;
+        }
+    };
+
+    if let Err(code) = write_config_value(&config) {
+        return code;
+    }
+    println!("Sealed {} protected file(s) in {}", sealed, CONFIG_FILE);
+    0
+}
+
+// new：生成习题骨架并登记到配置文件末尾
+pub fn new_exercise(options: &NewOptions) -> i32 {
+    let mut config = match read_config_value() {
+        Ok(config) => config,
+        Err(code) => return code,
+    };
+    let score = options
+        .score
+        .unwrap_or_else(|| scaffold::default_score(&config, options.difficulty));
+    let exercise = match NewExercise::new(
+        &options.name,
+        options.difficulty,
+        options.exercise_type,
+        score,
+    ) {
+        Ok(exercise) => exercise,
+        Err(e) => {
+            eprintln!("{}", e);
+            return EXIT_ERROR;
+        }
+    };
+
+    let registered = exercise
+        .check_available(&config)
+        .and_then(|()| {
+            exercise
+                .create_files()
+                .map_err(|e| format!("Failed to create exercise: {}", e))
+        })
+        .and_then(|()| exercise.register(&mut config));
+    if let Err(e) = registered {
+        eprintln!("{}", e);
+        return EXIT_ERROR;
+    }
+    if let Err(code) = write_config_value(&config) {
+        return code;
+    }
+
+    println!(
+        "Created {}/{} ({}, score {}) and registered it in {}",
+        EXERCISES_DIR,
+        exercise.path(),
+        exercise.exercise_type,
+        exercise.score,
+        CONFIG_FILE
+    );
+    println!("Fill in the test cases, then run 'cargotest seal' to record their hash.");
+    0
+}
+
+// reset：备份当前作答后，将习题中学生可修改的文件恢复为初始版本
+pub fn reset_exercise(name: &str, assume_yes: bool) -> i32 {
+    let (config, exercise) = match load_config()
+        .and_then(|config| find_exercise(&config, name).map(|exercise| (config, exercise)))
+    {
+        Ok(found) => found,
+        Err(code) => return code,
+    };
+    let Some(starter) = Starter::of(&exercise, config.starter_revision.as_deref()) else {
+        eprintln!(
+            "No starter version for {}: set `starter` for the exercise or `starter_revision` in {}",
+            exercise.name, CONFIG_FILE
+        );
+        return EXIT_ERROR;
+    };
+    // 先读取初始版本，读取失败时不修改任何文件
+    let files = match reset::starter_files(&exercise, &starter) {
+        Ok(files) => files,
+        Err(e) => {
+            eprintln!(
+                "Failed to read the starter version of {} from {}: {}",
+                exercise.name, starter, e
+            );
+            return EXIT_ERROR;
+        }
+    };
+
+    let question = format!(
+        "Reset {} to its starter version from {}? Test files are kept and your current files are backed up first.",
+        exercise.name, starter
+    );
+    if !assume_yes && !confirm(&question) {
+        println!("Reset cancelled.");
+        return 0;
+    }
+
+    let backup = match reset::backup(&exercise) {
+        Ok(backup) => backup,
+        Err(e) => {
+            eprintln!("Failed to back up {}: {}", exercise.name, e);
+            return EXIT_ERROR;
+        }
+    };
+    println!("Backed up the current files to {}", backup.display());
+    match reset::restore(&exercise, &files) {
+        Ok(restored) => {
+            println!(
+                "Restored {} file(s) of {} from {}",
+                restored.len(),
+                exercise.name,
+                starter
+            );
+            0
+        }
+        Err(e) => {
+            eprintln!("Failed to reset {}: {}", exercise.name, e);
+            EXIT_ERROR
+        }
+    }
+}
+
+// 询问用户并读取一行回答，只有 y 或 yes 视为同意
+fn confirm(question: &str) -> bool {
+    print!("{} [y/N] ", question);
+    if io::stdout().flush().is_err() {
+        return false;
+    }
+    let mut answer = String::new();
+    match io::stdin().read_line(&mut answer) {
+        Ok(_) => matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"),
+        Err(_) => false,
+    }
+}
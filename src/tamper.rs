@@ -0,0 +1,144 @@
+use crate::{Difficulty, Exercise, ExerciseType, EXERCISES_DIR};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// protected 中表示单文件习题 #[cfg(test)] 测试模块的键
+pub const TEST_MODULE: &str = "#[cfg(test)]";
+
+// 受保护内容的 SHA-256（小写十六进制）
+pub fn protected_hash(exercise_path: &Path, key: &str) -> io::Result<String> {
+    let content = protected_content(exercise_path, key)?;
+    Ok(Sha256::digest(content)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+// 读取受保护的内容：TEST_MODULE 为单文件习题的测试模块，其余为习题目录下的相对路径
+fn protected_content(exercise_path: &Path, key: &str) -> io::Result<Vec<u8>> {
+    if key != TEST_MODULE {
+        return fs::read(exercise_path.join(key));
+    }
+    let source = fs::read_to_string(exercise_path)?;
+    test_module(&source)
+        .map(|module| module.as_bytes().to_vec())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "no #[cfg(test)] module"))
+}
+
+// 从第一行 #[cfg(test)] 到文件末尾，习题的测试模块总是位于文件最后
+pub fn test_module(source: &str) -> Option<&str> {
+    let mut offset = 0;
+    for line in source.split_inclusive('\n') {
+        if line.trim() == TEST_MODULE {
+            return Some(&source[offset..]);
+        }
+        offset += line.len();
+    }
+    None
+}
+
+// 返回内容与配置中的哈希不一致或已无法读取的受保护文件
+pub fn tampered_files(exercise: &Exercise) -> Vec<String> {
+    let exercise_path = Path::new(EXERCISES_DIR).join(&exercise.path);
+    exercise
+        .protected
+        .iter()
+        .filter(|(key, expected)| {
+            protected_hash(&exercise_path, key).map_or(true, |hash| &hash != *expected)
+        })
+        .map(|(key, _)| key.clone())
+        .collect()
+}
+
+// 按当前文件内容重新计算配置中全部受保护文件的哈希，返回更新的条目数
+pub fn seal(config: &mut Value) -> Result<usize, Vec<String>> {
+    let mut sealed = 0;
+    let mut errors = Vec::new();
+    for difficulty in Difficulty::ALL {
+        let Some(exercises) = config
+            .get_mut(difficulty.as_str())
+            .and_then(Value::as_array_mut)
+        else {
+            continue;
+        };
+        for exercise in exercises {
+            let name = exercise["name"].as_str().unwrap_or_default().to_string();
+            let path = Path::new(EXERCISES_DIR).join(exercise["path"].as_str().unwrap_or_default());
+            let Some(protected) = exercise.get_mut("protected").and_then(Value::as_object_mut)
+            else {
+                continue;
+            };
+            for (key, hash) in protected.iter_mut() {
+                match protected_hash(&path, key) {
+                    Ok(new_hash) => {
+                        *hash = Value::String(new_hash);
+                        sealed += 1;
+                    }
+                    Err(e) => errors.push(format!("{}: {}: {}", name, key, e)),
+                }
+            }
+        }
+    }
+    if errors.is_empty() {
+        Ok(sealed)
+    } else {
+        Err(errors)
+    }
+}
+
+// 检查 protected 的键是否适用于该类型的习题
+pub fn check_key(key: &str, exercise_type: ExerciseType) -> Result<(), String> {
+    match exercise_type {
+        ExerciseType::SingleFile if key != TEST_MODULE => Err(format!(
+            "single_file exercises can only protect \"{}\"",
+            TEST_MODULE
+        )),
+        ExerciseType::CargoProject if key == TEST_MODULE => Err(format!(
+            "\"{}\" is only supported for single_file exercises",
+            TEST_MODULE
+        )),
+        ExerciseType::CargoProject
+            if Path::new(key).is_absolute() || key.split('/').any(|part| part == "..") =>
+        {
+            Err("expected a path relative to the exercise directory".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_module_starts_at_first_cfg_test_line() {
+        let source = "pub fn solve() {}\n\n#[cfg(test)]\nmod tests {\n    #[cfg(test)]\n}\n";
+        assert_eq!(
+            test_module(source),
+            Some("#[cfg(test)]\nmod tests {\n    #[cfg(test)]\n}\n")
+        );
+    }
+
+    #[test]
+    fn test_module_allows_indentation_and_crlf() {
+        let source = "pub fn solve() {}\r\n  #[cfg(test)]\r\nmod tests {}\r\n";
+        assert_eq!(
+            test_module(source),
+            Some("  #[cfg(test)]\r\nmod tests {}\r\n")
+        );
+    }
+
+    #[test]
+    fn test_module_missing() {
+        assert_eq!(test_module(""), None);
+        assert_eq!(test_module("pub fn solve() {}\n"), None);
+        // 只有整行为 #[cfg(test)] 时才算测试模块的开始
+        assert_eq!(
+            test_module("// #[cfg(test)]\n#[cfg(test)] mod tests {}\n"),
+            None
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use crate::command::run_timed;
+use crate::sandbox::ResourceLimits;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::Path;
+use std::process::Command;
+use std::time::Instant;
+
+// 未在配置中指定时，每个有时间预算的测试重复运行的次数
+pub const DEFAULT_REPETITIONS: u32 = 3;
+
+// 单个测试的计时结果（毫秒）
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TestTiming {
+    pub name: String,
+    pub budget_ms: u64,
+    pub repetitions: u32,
+    pub median_ms: f64,
+    pub max_ms: f64,
+}
+
+impl TestTiming {
+    // 以中位数与预算比较，减少偶发抖动的影响
+    pub fn within_budget(&self) -> bool {
+        self.median_ms <= self.budget_ms as f64
+    }
+}
+
+// 计时的结论
+pub enum Measurement {
+    Timed(TestTiming),
+    // 某次运行中测试未通过
+    Failed,
+    TimedOut,
+}
+
+// 在 grader 的计时下单独运行一个测试若干次，统计每次运行的耗时
+pub fn measure_test(
+    test_binary: &Path,
+    working_dir: &Path,
+    test_name: &str,
+    budget_ms: u64,
+    repetitions: u32,
+    limits: &ResourceLimits,
+    deadline: Instant,
+) -> io::Result<Measurement> {
+    let mut samples = Vec::with_capacity(repetitions as usize);
+    for _ in 0..repetitions {
+        let mut command = Command::new(test_binary);
+        command
+            .args([test_name, "--exact", "--test-threads=1", "-q"])
+            .current_dir(working_dir)
+            .env("TMPDIR", working_dir);
+        limits.apply(&mut command);
+
+        match run_timed(&mut command, deadline)? {
+            Some((status, elapsed)) if status.success() => {
+                samples.push(elapsed.as_secs_f64() * 1000.0)
+            }
+            Some(_) => return Ok(Measurement::Failed),
+            None => return Ok(Measurement::TimedOut),
+        }
+    }
+
+    samples.sort_by(f64::total_cmp);
+    let middle = samples.len() / 2;
+    let median = if samples.len() % 2 == 0 {
+        (samples[middle - 1] + samples[middle]) / 2.0
+    } else {
+        samples[middle]
+    };
+    Ok(Measurement::Timed(TestTiming {
+        name: test_name.to_string(),
+        budget_ms,
+        repetitions,
+        median_ms: round_ms(median),
+        max_ms: round_ms(samples.last().copied().unwrap_or_default()),
+    }))
+}
+
+fn round_ms(ms: f64) -> f64 {
+    (ms * 100.0).round() / 100.0
+}
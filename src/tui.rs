@@ -0,0 +1,417 @@
+use crate::logging::strip_ansi;
+use crate::{
+    evaluate_exercise, round_score, Evaluation, EvaluationContext, Exercise, ExerciseOutput,
+    ExerciseStatus, LiveEvent, Phase,
+};
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::Duration;
+use termion::event::Key;
+use termion::input::TermRead;
+use termion::raw::IntoRawMode;
+use termion::screen::AlternateScreen;
+use termion::{clear, color, cursor, style};
+
+// 没有新事件时检查按键和终端尺寸的间隔
+const REFRESH: Duration = Duration::from_millis(100);
+
+// 习题列表中状态一列的宽度
+const STATUS_WIDTH: usize = 24;
+
+const FOOTER: &str = " Up/Down select  PgUp/PgDn scroll output  r re-run  q quit";
+
+// 列表中一道习题的实时状态
+enum RowState {
+    Queued,
+    Running(Phase),
+    Done(Evaluation),
+}
+
+struct Row {
+    state: RowState,
+    // 本次评测捕获的输出，已去除终端颜色代码
+    output: Vec<String>,
+}
+
+struct Dashboard<'a> {
+    exercises: &'a [Exercise],
+    partial_credit: bool,
+    rows: Vec<Row>,
+    selected: usize,
+    // 输出区域相对末尾向上滚动的行数，为 0 时跟随最新输出
+    scroll: usize,
+    size: (u16, u16),
+}
+
+impl<'a> Dashboard<'a> {
+    fn new(exercises: &'a [Exercise], partial_credit: bool) -> Self {
+        let rows = exercises
+            .iter()
+            .map(|_| Row {
+                state: RowState::Queued,
+                output: Vec::new(),
+            })
+            .collect();
+        Dashboard {
+            exercises,
+            partial_credit,
+            rows,
+            selected: 0,
+            scroll: 0,
+            size: (0, 0),
+        }
+    }
+
+    fn apply(&mut self, event: LiveEvent) {
+        match event {
+            LiveEvent::Phase(index, phase) => self.rows[index].state = RowState::Running(phase),
+            LiveEvent::Line(index, line) => {
+                let output = &mut self.rows[index].output;
+                output.extend(
+                    strip_ansi(&line)
+                        .lines()
+                        .map(|line| line.replace('\t', "    ")),
+                );
+            }
+            LiveEvent::Finished(index, evaluation) => {
+                self.rows[index].state = RowState::Done(*evaluation)
+            }
+        }
+    }
+
+    fn select(&mut self, offset: isize) {
+        let last = self.rows.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(offset).min(last);
+        self.scroll = 0;
+    }
+
+    fn scroll_output(&mut self, lines: isize) {
+        let max = self.rows[self.selected].output.len();
+        self.scroll = self.scroll.saturating_add_signed(lines).min(max);
+    }
+
+    // 已评测完成的习题可以重新评测，返回是否需要加入队列
+    fn rerun_selected(&mut self) -> bool {
+        let row = &mut self.rows[self.selected];
+        if !matches!(row.state, RowState::Done(_)) {
+            return false;
+        }
+        row.state = RowState::Queued;
+        row.output.clear();
+        self.scroll = 0;
+        true
+    }
+
+    fn running(&self) -> usize {
+        self.rows
+            .iter()
+            .filter(|row| matches!(row.state, RowState::Running(_)))
+            .count()
+    }
+
+    fn header(&self) -> String {
+        let mut score = 0.0;
+        let mut passed = 0;
+        let mut queued = 0;
+        for (exercise, row) in self.exercises.iter().zip(&self.rows) {
+            match &row.state {
+                RowState::Done(evaluation) => {
+                    score += evaluation.score(exercise, self.partial_credit);
+                    if evaluation.passed() {
+                        passed += 1;
+                    }
+                }
+                RowState::Queued => queued += 1,
+                RowState::Running(_) => {}
+            }
+        }
+        let max_score: i32 = self.exercises.iter().map(|exercise| exercise.score).sum();
+        format!(
+            " cargotest  Score: {}/{}  Passed: {}/{}  Running: {}  Queued: {}",
+            round_score(score),
+            max_score,
+            passed,
+            self.rows.len(),
+            self.running(),
+            queued
+        )
+    }
+
+    fn draw(&self, screen: &mut impl Write) -> io::Result<()> {
+        let (width, height) = (self.size.0 as usize, self.size.1 as usize);
+        if width < 20 || height < 4 {
+            return write!(
+                screen,
+                "{}{}Terminal too small",
+                clear::All,
+                cursor::Goto(1, 1)
+            );
+        }
+        let body_height = height - 2;
+        let name_width = self
+            .exercises
+            .iter()
+            .map(|exercise| exercise.name.len())
+            .max()
+            .unwrap_or(0);
+        let list_width = (name_width + STATUS_WIDTH + 3).min(width / 2);
+
+        write!(
+            screen,
+            "{}{}{}{}",
+            cursor::Goto(1, 1),
+            style::Invert,
+            pad(&self.header(), width),
+            style::Reset
+        )?;
+
+        // 选中的习题始终在列表可见范围内
+        let first_row = (self.selected + 1).saturating_sub(body_height);
+        let output = &self.rows[self.selected].output;
+        let output_end = output.len() - self.scroll.min(output.len());
+        let output_start = output_end.saturating_sub(body_height);
+        for line in 0..body_height {
+            write!(screen, "{}", cursor::Goto(1, line as u16 + 2))?;
+            let index = first_row + line;
+            match self.rows.get(index) {
+                Some(row) => self.draw_row(screen, index, row, name_width, list_width)?,
+                None => write!(screen, "{}", " ".repeat(list_width))?,
+            }
+            let text = output
+                .get(output_start + line)
+                .filter(|_| output_start + line < output_end)
+                .map_or("", String::as_str);
+            write!(
+                screen,
+                "│ {}{}",
+                fit(text, width.saturating_sub(list_width + 2)),
+                clear::UntilNewline
+            )?;
+        }
+
+        write!(
+            screen,
+            "{}{}{}{}",
+            cursor::Goto(1, height as u16),
+            style::Invert,
+            pad(FOOTER, width),
+            style::Reset
+        )?;
+        screen.flush()
+    }
+
+    fn draw_row(
+        &self,
+        screen: &mut impl Write,
+        index: usize,
+        row: &Row,
+        name_width: usize,
+        list_width: usize,
+    ) -> io::Result<()> {
+        let (label, fg): (&str, &dyn color::Color) = match &row.state {
+            RowState::Queued => ("queued", &color::Reset),
+            RowState::Running(phase) => (phase.label(), &color::Yellow),
+            RowState::Done(evaluation) => (
+                evaluation.status.as_str(),
+                match evaluation.status {
+                    ExerciseStatus::Passed => &color::Green,
+                    ExerciseStatus::InProgress => &color::Blue,
+                    _ => &color::Red,
+                },
+            ),
+        };
+        let marker = if index == self.selected { ">" } else { " " };
+        let name = format!(
+            "{} {:<width$} ",
+            marker,
+            self.exercises[index].name,
+            width = name_width
+        );
+        let name = fit(&name, list_width);
+        let status = pad(label, list_width - display_width(&name));
+        if index == self.selected {
+            write!(screen, "{}{}{}", style::Bold, name, style::Reset)?;
+        } else {
+            write!(screen, "{}", name)?;
+        }
+        write!(
+            screen,
+            "{}{}{}",
+            color::Fg(fg),
+            status,
+            color::Fg(color::Reset)
+        )
+    }
+}
+
+// 全屏 TUI：在后台评测所选习题并实时显示状态，返回各习题的结果，
+// 退出时尚未评测的习题为 None
+pub fn run_dashboard(
+    jobs: usize,
+    exercises: &[Exercise],
+    context: &EvaluationContext,
+    partial_credit: bool,
+) -> io::Result<Vec<Option<Evaluation>>> {
+    if !termion::is_tty(&io::stdin()) || !termion::is_tty(&io::stdout()) {
+        return Err(io::Error::other("tui needs an interactive terminal"));
+    }
+
+    let mut dashboard = Dashboard::new(exercises, partial_credit);
+    let (live_sender, live_events) = mpsc::channel();
+    let (job_sender, job_receiver) = mpsc::channel();
+    for index in 0..exercises.len() {
+        let _ = job_sender.send(index);
+    }
+    let job_receiver = Mutex::new(job_receiver);
+    let stop = AtomicBool::new(false);
+    let keys = spawn_key_reader();
+
+    let ui = thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, exercises.len().max(1)) {
+            let live_sender = live_sender.clone();
+            let (job_receiver, stop) = (&job_receiver, &stop);
+            scope.spawn(move || loop {
+                let Ok(index) = job_receiver.lock().unwrap().recv() else {
+                    break;
+                };
+                // 退出后不再开始新的评测
+                if stop.load(Ordering::SeqCst) {
+                    continue;
+                }
+                let mut output = ExerciseOutput::live(index, live_sender.clone());
+                let evaluation = evaluate_exercise(&exercises[index], context, &mut output);
+                let _ = live_sender.send(LiveEvent::Finished(index, Box::new(evaluation)));
+            });
+        }
+
+        let ui = run_event_loop(&mut dashboard, &live_events, &keys, &job_sender);
+        stop.store(true, Ordering::SeqCst);
+        drop(job_sender);
+        let running = dashboard.running();
+        if running > 0 {
+            println!("Waiting for {} running exercise(s) to finish...", running);
+        }
+        ui
+    });
+
+    drop(live_sender);
+    for event in live_events {
+        dashboard.apply(event);
+    }
+    ui?;
+
+    Ok(dashboard
+        .rows
+        .into_iter()
+        .map(|row| match row.state {
+            RowState::Done(evaluation) => Some(evaluation),
+            _ => None,
+        })
+        .collect())
+}
+
+// 处理评测事件和按键，直到用户退出；离开时恢复终端
+fn run_event_loop(
+    dashboard: &mut Dashboard,
+    live_events: &mpsc::Receiver<LiveEvent>,
+    keys: &mpsc::Receiver<Key>,
+    job_sender: &mpsc::Sender<usize>,
+) -> io::Result<()> {
+    let mut screen = AlternateScreen::from(io::stdout().into_raw_mode()?);
+    write!(screen, "{}{}", cursor::Hide, clear::All)?;
+    let mut dirty = true;
+    loop {
+        let size = termion::terminal_size()?;
+        if size != dashboard.size {
+            dashboard.size = size;
+            write!(screen, "{}", clear::All)?;
+            dirty = true;
+        }
+        if dirty {
+            dashboard.draw(&mut screen)?;
+            dirty = false;
+        }
+
+        let page = dashboard.size.1.saturating_sub(2) as isize;
+        while let Ok(key) = keys.try_recv() {
+            dirty = true;
+            match key {
+                Key::Char('q') | Key::Esc | Key::Ctrl('c') => {
+                    write!(screen, "{}", cursor::Show)?;
+                    return screen.flush();
+                }
+                Key::Up | Key::Char('k') => dashboard.select(-1),
+                Key::Down | Key::Char('j') => dashboard.select(1),
+                Key::PageUp => dashboard.scroll_output(page),
+                Key::PageDown => dashboard.scroll_output(-page),
+                Key::Char('r') => {
+                    if dashboard.rerun_selected() {
+                        let _ = job_sender.send(dashboard.selected);
+                    }
+                }
+                _ => dirty = false,
+            }
+        }
+
+        if let Ok(event) = live_events.recv_timeout(REFRESH) {
+            dashboard.apply(event);
+            while let Ok(event) = live_events.try_recv() {
+                dashboard.apply(event);
+            }
+            dirty = true;
+        }
+    }
+}
+
+// 在后台线程中读取按键；该线程阻塞在标准输入上，随进程退出
+fn spawn_key_reader() -> mpsc::Receiver<Key> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for key in io::stdin().keys() {
+            match key {
+                Ok(key) if sender.send(key).is_ok() => {}
+                _ => break,
+            }
+        }
+    });
+    receiver
+}
+
+// 终端中的显示宽度，中日韩文字和全角符号占两列
+fn char_width(c: char) -> usize {
+    match c as u32 {
+        0x1100..=0x115f
+        | 0x2e80..=0xa4cf
+        | 0xac00..=0xd7a3
+        | 0xf900..=0xfaff
+        | 0xfe30..=0xfe4f
+        | 0xff00..=0xff60
+        | 0xffe0..=0xffe6
+        | 0x20000..=0x3fffd => 2,
+        _ => 1,
+    }
+}
+
+fn display_width(text: &str) -> usize {
+    text.chars().map(char_width).sum()
+}
+
+// 截断到指定的显示宽度
+fn fit(text: &str, width: usize) -> String {
+    let mut used = 0;
+    text.chars()
+        .take_while(|&c| {
+            used += char_width(c);
+            used <= width
+        })
+        .collect()
+}
+
+// 截断或用空格补齐到指定的显示宽度
+fn pad(text: &str, width: usize) -> String {
+    let text = fit(text, width);
+    let padding = width - display_width(&text);
+    text + &" ".repeat(padding)
+}
@@ -0,0 +1,243 @@
+use crate::{
+    evaluate_all, evaluate_exercise, Evaluation, EvaluationContext, Exercise, ExerciseOutput,
+    ExerciseStatus,
+};
+use std::collections::{BTreeSet, HashMap};
+use std::ffi::{CString, OsStr};
+use std::fs;
+use std::io::{self, BufRead};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+// 收到第一个事件后再等待一小段时间，把编辑器连续写入合并为一次评测
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+const WATCH_MASK: u32 = libc::IN_CLOSE_WRITE
+    | libc::IN_MOVED_TO
+    | libc::IN_MOVED_FROM
+    | libc::IN_CREATE
+    | libc::IN_DELETE;
+
+// 基于 inotify 监听目录树中的文件变化
+pub struct FileWatcher {
+    fd: OwnedFd,
+    dirs: HashMap<i32, PathBuf>,
+}
+
+impl FileWatcher {
+    pub fn new(root: &Path) -> io::Result<Self> {
+        let fd = unsafe { libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let mut watcher = FileWatcher {
+            fd: unsafe { OwnedFd::from_raw_fd(fd) },
+            dirs: HashMap::new(),
+        };
+        watcher.watch_recursive(root)?;
+        Ok(watcher)
+    }
+
+    fn watch_recursive(&mut self, dir: &Path) -> io::Result<()> {
+        let c_path = CString::new(dir.as_os_str().as_bytes())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let wd =
+            unsafe { libc::inotify_add_watch(self.fd.as_raw_fd(), c_path.as_ptr(), WATCH_MASK) };
+        if wd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        self.dirs.insert(wd, dir.to_path_buf());
+
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() && !is_ignored_dir(&entry.path()) {
+                self.watch_recursive(&entry.path())?;
+            }
+        }
+        Ok(())
+    }
+
+    // 读取当前已到达的全部事件，返回发生变化的文件路径
+    fn read_changes(&mut self) -> io::Result<Vec<PathBuf>> {
+        let mut changes = Vec::new();
+        let mut buffer = [0u8; 4096];
+        loop {
+            let len = unsafe {
+                libc::read(
+                    self.fd.as_raw_fd(),
+                    buffer.as_mut_ptr() as *mut libc::c_void,
+                    buffer.len(),
+                )
+            };
+            if len < 0 {
+                let err = io::Error::last_os_error();
+                if err.kind() == io::ErrorKind::WouldBlock {
+                    return Ok(changes);
+                }
+                return Err(err);
+            }
+
+            let mut offset = 0;
+            while offset < len as usize {
+                let event = &buffer[offset..];
+                let wd = i32::from_ne_bytes(event[0..4].try_into().unwrap());
+                let mask = u32::from_ne_bytes(event[4..8].try_into().unwrap());
+                let name_len = u32::from_ne_bytes(event[12..16].try_into().unwrap()) as usize;
+                let name = &event[16..16 + name_len];
+                let name = &name[..name.iter().position(|&b| b == 0).unwrap_or(name.len())];
+                offset += 16 + name_len;
+
+                let Some(dir) = self.dirs.get(&wd) else {
+                    continue;
+                };
+                let path = dir.join(OsStr::from_bytes(name));
+                if mask & libc::IN_ISDIR != 0 {
+                    // 新建的子目录同样需要监听
+                    if mask & (libc::IN_CREATE | libc::IN_MOVED_TO) != 0 && !is_ignored_dir(&path) {
+                        let _ = self.watch_recursive(&path);
+                    }
+                } else if is_source_file(&path) {
+                    changes.push(path);
+                }
+            }
+        }
+    }
+}
+
+// 评测过程中 cargo 会写入 target 目录，这些变化不应触发重新评测
+fn is_ignored_dir(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name == "target" || name.starts_with('.'))
+}
+
+// 只关心习题源文件的变化，忽略测试二进制文件和 Cargo.lock 等评测产物
+fn is_source_file(path: &Path) -> bool {
+    let file_name = path.file_name().and_then(|name| name.to_str());
+    let extension = path.extension().and_then(|ext| ext.to_str());
+    matches!(extension, Some("rs") | Some("json")) || file_name == Some("Cargo.toml")
+}
+
+// 监听模式下等待的事件
+enum WatchEvent {
+    Changed(Vec<PathBuf>),
+    Quit,
+}
+
+// 等待文件变化或用户输入 q 退出
+fn wait_for_event(watcher: &mut FileWatcher, stdin_open: &mut bool) -> io::Result<WatchEvent> {
+    loop {
+        let mut fds = vec![libc::pollfd {
+            fd: watcher.fd.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        }];
+        if *stdin_open {
+            fds.push(libc::pollfd {
+                fd: io::stdin().as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            });
+        }
+
+        let ready = unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, -1) };
+        if ready < 0 {
+            let err = io::Error::last_os_error();
+            if err.kind() == io::ErrorKind::Interrupted {
+                continue;
+            }
+            return Err(err);
+        }
+
+        if fds.len() > 1 && fds[1].revents != 0 {
+            let mut input = String::new();
+            if io::stdin().lock().read_line(&mut input)? == 0 {
+                // 标准输入已关闭（例如重定向自 /dev/null），此后只监听文件
+                *stdin_open = false;
+            } else if input.trim().eq_ignore_ascii_case("q") {
+                return Ok(WatchEvent::Quit);
+            }
+        }
+
+        if fds[0].revents != 0 {
+            let mut changes = watcher.read_changes()?;
+            thread::sleep(DEBOUNCE);
+            changes.extend(watcher.read_changes()?);
+            if !changes.is_empty() {
+                return Ok(WatchEvent::Changed(changes));
+            }
+        }
+    }
+}
+
+fn print_progress(exercises: &[Exercise], results: &[Evaluation]) {
+    let passed = results
+        .iter()
+        .filter(|evaluation| evaluation.passed())
+        .count();
+    let in_progress = results
+        .iter()
+        .filter(|evaluation| evaluation.status == ExerciseStatus::InProgress)
+        .count();
+    println!(
+        "\nProgress: {}/{} exercises passed, {} in progress.",
+        passed,
+        exercises.len(),
+        in_progress
+    );
+    // 仍带有 I AM NOT DONE 标记的习题在标记删除前不参与评测
+    if let Some((exercise, _)) = exercises.iter().zip(results).find(|(_, evaluation)| {
+        !evaluation.passed() && evaluation.status != ExerciseStatus::InProgress
+    }) {
+        println!("Next exercise to solve: {}", exercise.name);
+    } else if in_progress > 0 {
+        println!("Remove the `// I AM NOT DONE` line from an exercise to have it evaluated.");
+    }
+    println!("Watching exercises/ for changes. Type 'q' and press Enter to quit.");
+}
+
+// 监听模式：先完整评测一遍，之后只重新评测文件发生变化的习题，
+// 直到全部通过或用户退出
+pub fn watch_exercises(
+    exercises_root: &Path,
+    jobs: usize,
+    exercises: &[Exercise],
+    context: &EvaluationContext,
+) -> io::Result<Vec<Evaluation>> {
+    let mut watcher = FileWatcher::new(exercises_root)?;
+    let mut results = evaluate_all(jobs, exercises, context);
+    let mut stdin_open = true;
+
+    while results.iter().any(|evaluation| !evaluation.passed()) {
+        print_progress(exercises, &results);
+
+        let changes = match wait_for_event(&mut watcher, &mut stdin_open)? {
+            WatchEvent::Changed(changes) => changes,
+            WatchEvent::Quit => return Ok(results),
+        };
+
+        let changed: BTreeSet<usize> = changes
+            .iter()
+            .filter_map(|path| {
+                exercises
+                    .iter()
+                    .position(|exercise| path.starts_with(exercises_root.join(&exercise.path)))
+            })
+            .collect();
+
+        for index in changed {
+            let mut output = ExerciseOutput::default();
+            results[index] = evaluate_exercise(&exercises[index], context, &mut output);
+            output.flush();
+        }
+
+        // 评测本身产生的文件事件不应再次触发评测
+        watcher.read_changes()?;
+    }
+
+    println!("\nAll exercises passed!");
+    Ok(results)
+}